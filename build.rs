@@ -0,0 +1,121 @@
+//! Generates the stubs tables for the selected Tcl version.
+//!
+//! The declarations are read from the `.decls` files under `stubs/`, one
+//! directory per supported Tcl version, and written to `$OUT_DIR/stubs.rs`
+//! for `src/interpreter/stubs.rs` to include.
+
+#[path = "stubs/generate.rs"]
+mod generate;
+
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+use generate::Generator;
+use generate::Platform;
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=stubs");
+
+    let out = Path::new(&env::var_os("OUT_DIR").unwrap()).join("stubs.rs");
+    let tcl86 = env::var_os("CARGO_FEATURE_TCL86").is_some();
+    let tcl90 = env::var_os("CARGO_FEATURE_TCL90").is_some();
+    let (version, major) = match (tcl86, tcl90) {
+        (true, false) => ("tcl8.6", 8),
+        (false, true) => ("tcl9.0", 9),
+        _ => {
+            // The crate itself reports the misconfigured features.
+            fs::write(&out, "").unwrap();
+            return;
+        }
+    };
+
+    if env::var_os("CARGO_FEATURE_EMBED").is_some() {
+        check_tcl_library(version);
+    }
+
+    let platform = Platform::from_target_os(&env::var("CARGO_CFG_TARGET_OS").unwrap());
+    let mut generator = Generator::new(major, platform);
+    // Tcl 8.6 predates the parent/child names rtea uses for these functions.
+    generator.rename("Tcl_CreateSlave", "Tcl_CreateChild");
+    generator.rename("Tcl_GetMaster", "Tcl_GetParent");
+    generator.rename("Tcl_GetSlave", "Tcl_GetChild");
+
+    let dir = Path::new("stubs").join(version);
+    let mut decls: Vec<_> = fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "decls"))
+        .collect();
+    decls.sort();
+    for path in decls {
+        let script = fs::read_to_string(&path).unwrap();
+        if let Err(err) = generator.parse(&script) {
+            panic!("{}: {}", path.display(), err);
+        }
+    }
+
+    match generator.generate() {
+        Ok(code) => fs::write(&out, code).unwrap(),
+        Err(err) => panic!("{}: {}", dir.display(), err),
+    }
+}
+
+/// Turns a missing Tcl library for the `embed` feature into a clear build
+/// error rather than a linker failure.
+///
+/// `TCL_LIB_DIR` names the directory of the library, which is then trusted
+/// without a check.  Otherwise, the check only fails when the library of
+/// the other Tcl version is found but not the selected one, which is what
+/// building with the default `tcl90` feature on a host with only Tcl 8.6
+/// looks like.
+fn check_tcl_library(version: &str) {
+    println!("cargo:rerun-if-env-changed=TCL_LIB_DIR");
+    println!("cargo:rerun-if-env-changed=LIBRARY_PATH");
+
+    if let Some(dir) = env::var_os("TCL_LIB_DIR") {
+        println!(
+            "cargo:rustc-link-search=native={}",
+            Path::new(&dir).display()
+        );
+        return;
+    }
+
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    if let Some(paths) = env::var_os("LIBRARY_PATH") {
+        dirs.extend(env::split_paths(&paths));
+    }
+    let arch = env::var("CARGO_CFG_TARGET_ARCH").unwrap_or_default();
+    dirs.push(PathBuf::from(format!("/usr/lib/{}-linux-gnu", arch)));
+    for dir in [
+        "/usr/lib",
+        "/usr/lib64",
+        "/usr/local/lib",
+        "/opt/homebrew/lib",
+    ] {
+        dirs.push(PathBuf::from(dir));
+    }
+
+    let found = |version: &str| {
+        dirs.iter().any(|dir| {
+            ["so", "dylib", "a"]
+                .iter()
+                .any(|ext| dir.join(format!("lib{}.{}", version, ext)).exists())
+        })
+    };
+    let (other, feature) = if version == "tcl8.6" {
+        ("tcl9.0", "tcl90")
+    } else {
+        ("tcl8.6", "tcl86")
+    };
+    if !found(version) && found(other) {
+        panic!(
+            "the `embed` feature links against lib{}, but only lib{} was found; \
+             build with the `{}` feature instead (e.g., `--no-default-features \
+             --features {},testing`) or set TCL_LIB_DIR to the directory of lib{}",
+            version, other, feature, feature, version
+        );
+    }
+}
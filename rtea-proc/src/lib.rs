@@ -26,13 +26,13 @@
 //! #[module_unload(Example)]
 //! fn unload(interp: &Interpreter) -> Result<TclStatus, String> {
 //!     safe_unload(interp, args)?;
-//!     // Remove the additional commands that were not considered "safe"...
+//!     // Release resources that were not registered through rtea...
 //!     Ok(TclStatus::Ok)
 //! }
 //!
 //! #[module_safe_unload(Example)]
 //! fn safe_unload(_interp: &Interpreter) -> Result<TclStatus, String> {
-//!     // Remove the "safe" set of commands
+//!     // The commands created by `safe_init` are deleted automatically.
 //!     Ok(TclStatus::Ok)
 //! }
 //! ```
@@ -87,8 +87,11 @@ fn module_init_common(prefix: &str, attr: TokenStream, item: TokenStream) -> Tok
                 pub extern "C" fn {module_symbol}_{prefix}Init(interp: *const Interpreter) -> TclStatus {{
                     Interpreter::from_raw(interp)
                         .map(|interp| {{
-                            interp.init_global_functions();
-                            {init_fn}(interp)
+                            if let Err(e) = interp.init_global_functions() {{
+                                interp.set_result(&e.to_string());
+                                return TclStatus::Error;
+                            }}
+                            interp.init_module("{module_symbol}", {init_fn})
                                 .and(interp.provide_package("{module_tcl}", {version}))
                                 .unwrap_or_else(|s| {{interp.set_result(&s); TclStatus::Error}})
                         }})
@@ -216,7 +219,7 @@ fn module_unload_common(prefix: &str, attr: TokenStream, item: TokenStream) -> T
                 #[no_mangle]
                 pub extern "C" fn {module_symbol}_{prefix}Unload(interp: *const Interpreter, flags: TclUnloadFlag) -> TclStatus {{
                     Interpreter::from_raw(interp)
-                        .map(|interp| {unload_fn}(interp, flags)
+                        .map(|interp| interp.unload_module("{module_symbol}", flags, {unload_fn})
                             .unwrap_or_else(|s| {{interp.set_result(&s); TclStatus::Error}}))
                         .unwrap_or(TclStatus::Error)
                 }}
@@ -243,6 +246,14 @@ fn module_unload_common(prefix: &str, attr: TokenStream, item: TokenStream) -> T
 ///
 /// and the module's name (as given to [module_init]) should be given as the
 /// sole attribute to the macro.
+///
+/// The commands, object types, traces and state that the initialization
+/// routine registered with the interpreter through rtea are recorded by
+/// [module_init] and removed automatically once the unload routine returns
+/// successfully, so it only needs to release anything else.  When the
+/// module is about to be unmapped from the process, the unload is refused
+/// while objects of its object types are still alive (see
+/// `Interpreter::unload_module`).
 #[proc_macro_attribute]
 pub fn module_unload(attr: TokenStream, item: TokenStream) -> TokenStream {
     module_unload_common("", attr, item)
@@ -259,7 +270,8 @@ pub fn module_unload(attr: TokenStream, item: TokenStream) -> TokenStream {
 /// ```
 ///
 /// and the module's name (as given to [module_init]) should be given as the
-/// sole attribute to the macro.
+/// sole attribute to the macro.  What the "safe" initialization routine
+/// registered is removed automatically, as described for [module_unload].
 #[proc_macro_attribute]
 pub fn module_safe_unload(attr: TokenStream, item: TokenStream) -> TokenStream {
     module_unload_common("Safe", attr, item)
@@ -287,10 +299,13 @@ pub fn generate_tcl_object(item: TokenStream) -> TokenStream {
     out_stream.extend(
         TokenStream::from_str(&format!(
             r#"
+                static {tcl_obj_name}_LIVE: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
                 extern "C" fn {obj_name}_tcl_free(obj: *mut RawObject) {{
                     unsafe {{
                         drop(Box::from_raw((*obj).ptr1 as *mut {obj_name}));
                     }}
+                    {tcl_obj_name}_LIVE.fetch_sub(1, std::sync::atomic::Ordering::Relaxed);
                 }}
 
                 extern "C" fn {obj_name}_tcl_dup(obj: *const RawObject, new_obj: *mut RawObject) {{
@@ -299,6 +314,7 @@ pub fn generate_tcl_object(item: TokenStream) -> TokenStream {
                         (*new_obj).ptr1 = new_rep;
                         (*new_obj).obj_type = (&{tcl_obj_name}) as *const ObjectType;
                     }}
+                    {tcl_obj_name}_LIVE.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
                 }}
 
                 extern "C" fn {obj_name}_tcl_update(obj: *mut RawObject) {{
@@ -306,7 +322,7 @@ pub fn generate_tcl_object(item: TokenStream) -> TokenStream {
                         let inner = ((*obj).ptr1 as *mut {obj_name}).as_ref().unwrap();
                         let (tcl_str, tcl_str_len) = rtea::tcl_string(&inner.as_string());
                         (*obj).bytes = tcl_str;
-                        (*obj).length = tcl_str_len as usize;
+                        (*obj).length = tcl_str_len;
                     }}
                 }}
 
@@ -363,12 +379,17 @@ pub fn generate_tcl_object(item: TokenStream) -> TokenStream {
                             (*obj.obj).obj_type = (&{tcl_obj_name}) as *const rtea::ObjectType;
                             (*obj.obj).bytes = std::ptr::null_mut();
                         }}
+                        {tcl_obj_name}_LIVE.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
                         obj
                     }}
 
                     fn type_name() -> &'static str {{ "{obj_name}" }}
 
                     fn tcl_type() -> &'static ObjectType {{ &{tcl_obj_name} }}
+
+                    fn live_objects() -> usize {{
+                        {tcl_obj_name}_LIVE.load(std::sync::atomic::Ordering::Relaxed)
+                    }}
                 }}
 
                 impl From<{obj_name}> for rtea::Object {{
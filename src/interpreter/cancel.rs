@@ -0,0 +1,166 @@
+//! Wrappers around Tcl's [script cancellation](https://www.tcl.tk/man/tcl/TclLib/Eval.html)
+//! facilities.
+
+use std::any::TypeId;
+use std::ffi::CString;
+use std::ffi::c_int;
+use std::ffi::c_void;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::PoisonError;
+
+use crate::EvalError;
+use crate::Interpreter;
+use crate::Object;
+use crate::RawObject;
+
+const TCL_LEAVE_ERR_MSG: c_int = 0x200;
+const TCL_CANCEL_UNWIND: c_int = 0x100000;
+
+type CancelEvalProc =
+    extern "C" fn(*const Interpreter, *mut RawObject, *mut c_void, c_int) -> c_int;
+
+/// The interpreter a [CancelHandle] cancels, until it is deleted.
+#[derive(Debug)]
+struct Target(*const Interpreter);
+
+// The pointer is only passed to `Tcl_CancelEval`, which may be called from
+// any thread.
+unsafe impl Send for Target {}
+
+/// The target shared by all handles of an interpreter.
+type SharedTarget = Arc<Mutex<Option<Target>>>;
+
+/// Builds the AssocData key of the shared target.
+///
+/// The `TypeId` keeps extensions bundling different builds of rtea apart.
+fn target_key() -> CString {
+    CString::new(format!("rtea::cancel::{:?}", TypeId::of::<Target>()))
+        .expect("type ids have no Nul")
+}
+
+extern "C" fn clear_target(target: *mut c_void, _interp: *const Interpreter) {
+    let target = unsafe { Box::from_raw(target as *mut SharedTarget) };
+    // Taking the lock waits for cancellations in progress on other threads.
+    *target.lock().unwrap_or_else(PoisonError::into_inner) = None;
+}
+
+/// A handle for cancelling scripts running in an interpreter from any thread.
+///
+/// Created by [cancel_handle](Interpreter::cancel_handle).  Unlike an
+/// [Interpreter] reference, the handle may be sent to and shared with other
+/// threads (e.g., a supervisor enforcing a deadline).  `Tcl_CancelEval` is
+/// the only function it calls and that function is safe to call from any
+/// thread.
+///
+/// The handles of an interpreter share a slot which is cleared when the
+/// interpreter is deleted, so cancelling an interpreter that has since been
+/// deleted has no effect and reports an error.
+#[derive(Debug, Clone)]
+pub struct CancelHandle {
+    target: SharedTarget,
+    cancel_eval: CancelEvalProc,
+}
+
+impl CancelHandle {
+    /// Cancels the script currently being evaluated in the interpreter.
+    ///
+    /// The script fails with `message` (or Tcl's default "eval canceled"
+    /// message) and an `-errorcode` starting with `TCL CANCEL`.  The rest of
+    /// the error code depends on the Tcl version: Tcl 9.0 reports
+    /// `TCL CANCEL IEVAL` (or `TCL CANCEL IUNWIND` when unwinding), while
+    /// Tcl 8.6 reports `TCL CANCEL ICANCEL` followed by the message.  When
+    /// `unwind` is `false`, `catch` in the script can intercept the
+    /// cancellation; when `true`, the error unwinds all the way to the
+    /// outermost evaluation and cannot be caught.
+    pub fn cancel(&self, unwind: bool, message: Option<&str>) -> Result<(), String> {
+        let message = message.map(Object::new_string);
+        let raw = message.as_ref().map_or(std::ptr::null_mut(), |obj| obj.obj);
+        let flags = if unwind { TCL_CANCEL_UNWIND } else { 0 };
+
+        // The lock is held until Tcl returns, so the interpreter cannot be
+        // deleted in the meantime.
+        let target = self.target.lock().unwrap_or_else(PoisonError::into_inner);
+        let Some(Target(interp)) = *target else {
+            return Err("interpreter no longer exists".to_string());
+        };
+        if (self.cancel_eval)(interp, raw, std::ptr::null_mut(), flags) == 0 {
+            // `Tcl_CancelEval` releases the reference to the message once it
+            // finds the interpreter, so ownership was transferred.
+            std::mem::forget(message);
+            Ok(())
+        } else {
+            // Otherwise the message was left alone and is released here.
+            Err("interpreter no longer exists".to_string())
+        }
+    }
+}
+
+impl Interpreter {
+    /// Gets a handle which can cancel scripts in this interpreter from other
+    /// threads.
+    ///
+    /// See [CancelHandle] and the `interp cancel` command.
+    pub fn cancel_handle(&self) -> CancelHandle {
+        let key = target_key();
+        let existing = (self.stubs().Tcl_GetAssocData)(
+            self as *const Interpreter,
+            key.as_ptr(),
+            std::ptr::null_mut(),
+        );
+        let target = match unsafe { (existing as *const SharedTarget).as_ref() } {
+            Some(target) => target.clone(),
+            None => {
+                let target = Arc::new(Mutex::new(Some(Target(self as *const Interpreter))));
+                (self.stubs().Tcl_SetAssocData)(
+                    self as *const Interpreter,
+                    key.as_ptr(),
+                    Some(clear_target),
+                    Box::into_raw(Box::new(target.clone())) as *mut c_void,
+                );
+                target
+            }
+        };
+
+        CancelHandle {
+            target,
+            cancel_eval: self.stubs().Tcl_CancelEval,
+        }
+    }
+
+    /// Checks whether the script in progress has been canceled.
+    ///
+    /// Long-running commands implemented in Rust should call this
+    /// periodically.  If the script was canceled, the returned error carries
+    /// the cancellation message and `TCL CANCEL` error code, and returning
+    /// it from the command continues the unwinding Tcl started.
+    ///
+    /// ```rust
+    /// use rtea::*;
+    ///
+    /// fn busy(interp: &Interpreter, _args: Vec<Object>) -> Result<TclStatus, Object> {
+    ///     loop {
+    ///         interp.check_canceled()?;
+    ///         // ... do a bounded amount of work ...
+    ///     }
+    /// }
+    /// ```
+    pub fn check_canceled(&self) -> Result<(), EvalError> {
+        // Cancellation requests from other threads are delivered through an
+        // async handler, which Tcl only runs between commands.  Run pending
+        // handlers here so a busy Rust loop can observe them.
+        if (self.stubs().Tcl_AsyncReady)() != 0 {
+            let status = (self.stubs().Tcl_AsyncInvoke)(self as *const Interpreter, 0);
+            if status == 1 {
+                return Err(self.eval_error(status));
+            }
+        }
+
+        let status = (self.stubs().Tcl_Canceled)(self as *const Interpreter, TCL_LEAVE_ERR_MSG);
+        if status == 0 {
+            Ok(())
+        } else {
+            Err(self.eval_error(status))
+        }
+    }
+}
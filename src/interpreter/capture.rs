@@ -0,0 +1,257 @@
+//! Redirection of Tcl's standard output and error channels into memory,
+//! used by the [testing](crate::testing) module.
+//!
+//! The replacement channels are [reflected channels](https://www.tcl.tk/man/tcl/TclCmd/refchan.html)
+//! whose handler is implemented in Rust.  Tcl keeps one set of standard
+//! channels per thread, so the redirection affects every interpreter on the
+//! current thread until it is undone.
+
+use std::cell::Cell;
+use std::cell::RefCell;
+use std::ffi::CString;
+use std::ffi::c_int;
+use std::ffi::c_void;
+use std::rc::Rc;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+use super::stubs::Tcl_Channel;
+use crate::Interpreter;
+use crate::RawObject;
+use crate::TclStatus;
+use crate::tcl::*;
+
+/// A standard channel that can be captured.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum StdChannel {
+    Stdout,
+    Stderr,
+}
+
+impl StdChannel {
+    fn name(self) -> &'static str {
+        match self {
+            StdChannel::Stdout => "stdout",
+            StdChannel::Stderr => "stderr",
+        }
+    }
+
+    /// The `TCL_STDOUT`/`TCL_STDERR` value passed to `Tcl_SetStdChannel`.
+    fn tcl_type(self) -> c_int {
+        match self {
+            StdChannel::Stdout => 1 << 2,
+            StdChannel::Stderr => 1 << 3,
+        }
+    }
+}
+
+type Buffer = Rc<RefCell<Vec<u8>>>;
+
+/// The handler of a reflected channel collecting what is written to it.
+///
+/// This is an object command so that the data to write can be read as a
+/// byte array, without a round trip through its string representation.
+extern "C" fn capture_handler(
+    buffer: *mut c_void,
+    i: *const Interpreter,
+    argc: c_int,
+    argv: *const *mut RawObject,
+) -> c_int {
+    let interp = Interpreter::from_raw(i).expect("Tcl passed bad interpreter");
+    let buffer = unsafe { (buffer as *const Buffer).as_ref() }.expect("capture buffer corrupted!");
+    let args = unsafe { std::slice::from_raw_parts(argv, argc as usize) };
+    let method = args.get(1).map(|arg| RawObject::wrap(*arg));
+
+    match method.as_ref().map(|method| method.get_string()) {
+        Some("initialize") => interp.set_result("initialize finalize watch write"),
+        Some("write") => {
+            let Some(data) = args.get(3) else {
+                interp.set_result("missing data to write");
+                return TclStatus::Error as c_int;
+            };
+            let mut len: TclSize = 0;
+            let bytes = (interp.stubs().Tcl_GetByteArrayFromObj)(*data, &mut len);
+            if bytes.is_null() {
+                interp.set_result("data to write is not a byte array");
+                return TclStatus::Error as c_int;
+            }
+            let len = from_tcl_size(len);
+            buffer
+                .borrow_mut()
+                .extend_from_slice(unsafe { std::slice::from_raw_parts(bytes, len) });
+            interp.set_result(&len.to_string());
+        }
+        _ => interp.set_result(""),
+    }
+    TclStatus::Ok as c_int
+}
+
+extern "C" fn free_buffer(buffer: *mut c_void) {
+    unsafe { drop(Box::from_raw(buffer as *mut Buffer)) };
+}
+
+/// The channels a capture replaced and installed.
+struct Replacement {
+    which: StdChannel,
+    channel: Tcl_Channel,
+    /// The channel that was the standard channel before.
+    ///
+    /// Tcl counts a reference (see `Tcl_RegisterChannel`) for the standard
+    /// channel.  The capture holds that reference to the previous channel
+    /// until it is restored, and the standard channel slot a new one to the
+    /// capture's channel.
+    previous: Cell<Tcl_Channel>,
+}
+
+thread_local! {
+    /// The active captures of this thread, oldest first.
+    static REPLACEMENTS: RefCell<Vec<Rc<Replacement>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Numbers the handler commands, so captures of the same channel in the
+/// same interpreter do not replace each other's handler.
+static HANDLERS: AtomicUsize = AtomicUsize::new(0);
+
+/// A standard channel redirected into memory.
+///
+/// Dropping it restores the previous channel.  Captures may be dropped in
+/// any order: if a later capture of the same channel is still active, it
+/// takes over the channel to restore instead.
+pub(crate) struct CapturedChannel {
+    interp: *const Interpreter,
+    handler: String,
+    channel: String,
+    replacement: Rc<Replacement>,
+    buffer: Buffer,
+}
+
+impl CapturedChannel {
+    /// Gets what was written to the channel so far.
+    pub(crate) fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.buffer.borrow()).into_owned()
+    }
+
+    /// Gets what was written to the channel so far and clears it.
+    pub(crate) fn take(&self) -> String {
+        let contents = std::mem::take(&mut *self.buffer.borrow_mut());
+        String::from_utf8_lossy(&contents).into_owned()
+    }
+}
+
+impl Drop for CapturedChannel {
+    fn drop(&mut self) {
+        let replacement = &self.replacement;
+        let later = REPLACEMENTS.with_borrow_mut(|replacements| {
+            replacements.retain(|other| !Rc::ptr_eq(other, replacement));
+            replacements
+                .iter()
+                .find(|other| {
+                    other.which == replacement.which && other.previous.get() == replacement.channel
+                })
+                .cloned()
+        });
+
+        let Ok(interp) = Interpreter::from_raw(self.interp) else {
+            return;
+        };
+        let stubs = interp.stubs();
+        let which = replacement.which.tcl_type();
+        let previous = replacement.previous.get();
+        if let Some(later) = later {
+            // The later capture restores our previous channel in our place,
+            // taking over our reference to it, and releases its reference to
+            // our channel.
+            later.previous.set(previous);
+            (stubs.Tcl_UnregisterChannel)(std::ptr::null(), replacement.channel);
+        } else if (stubs.Tcl_GetStdChannel)(which) == replacement.channel {
+            // Our reference to the previous channel goes back to the slot,
+            // whose reference to our channel is released once it is no longer
+            // the standard channel (Tcl refuses to close standard channels).
+            (stubs.Tcl_SetStdChannel)(previous, which);
+            (stubs.Tcl_UnregisterChannel)(std::ptr::null(), replacement.channel);
+        } else if !previous.is_null() {
+            // A script replaced the standard channel.
+            (stubs.Tcl_UnregisterChannel)(std::ptr::null(), previous);
+        }
+        let _ = interp.eval(&format!("close {}", self.channel));
+        let _ = interp.delete_command(&self.handler);
+    }
+}
+
+impl Interpreter {
+    /// Redirects a standard channel of the current thread into memory.
+    ///
+    /// The replacement channel is registered in this interpreter, which
+    /// must outlive the returned [CapturedChannel].
+    pub(crate) fn capture_std_channel(&self, which: StdChannel) -> Result<CapturedChannel, String> {
+        let buffer = Buffer::default();
+        let handler = format!(
+            "::rtea::testing::{}{}",
+            which.name(),
+            HANDLERS.fetch_add(1, Ordering::Relaxed)
+        );
+        let name = CString::new(handler.as_str()).expect("handler names have no Nul");
+        let state = Box::into_raw(Box::new(buffer.clone()));
+        let token = (self.stubs().Tcl_CreateObjCommand)(
+            self as *const Interpreter,
+            name.as_ptr(),
+            Some(capture_handler),
+            state as *mut c_void,
+            Some(free_buffer),
+        );
+        if token.is_null() {
+            free_buffer(state as *mut c_void);
+            return Err(format!("cannot create {}", handler));
+        }
+
+        let channel = self.open_capture(&handler).inspect_err(|_| {
+            let _ = self.delete_command(&handler);
+        })?;
+        let name = CString::new(channel.as_str()).expect("Tcl channel names have no Nul");
+        let mut mode: c_int = 0;
+        let chan =
+            (self.stubs().Tcl_GetChannel)(self as *const Interpreter, name.as_ptr(), &mut mode);
+        if chan.is_null() {
+            let err = self.get_obj_result().get_string().to_string();
+            let _ = self.eval(&format!("close {}", channel));
+            let _ = self.delete_command(&handler);
+            return Err(err);
+        }
+
+        let previous = (self.stubs().Tcl_GetStdChannel)(which.tcl_type());
+        (self.stubs().Tcl_RegisterChannel)(std::ptr::null(), chan);
+        (self.stubs().Tcl_SetStdChannel)(chan, which.tcl_type());
+        let replacement = Rc::new(Replacement {
+            which,
+            channel: chan,
+            previous: Cell::new(previous),
+        });
+        REPLACEMENTS.with_borrow_mut(|replacements| replacements.push(replacement.clone()));
+
+        Ok(CapturedChannel {
+            interp: self as *const Interpreter,
+            handler,
+            channel,
+            replacement,
+            buffer,
+        })
+    }
+
+    /// Creates the reflected channel for the handler `handler`.
+    fn open_capture(&self, handler: &str) -> Result<String, String> {
+        let channel = self
+            .eval(&format!("chan create write {}", handler))
+            .map_err(|err| err.get_string().to_string())?
+            .get_string()
+            .to_string();
+        self.eval(&format!(
+            "chan configure {} -buffering none -encoding utf-8 -translation lf",
+            channel
+        ))
+        .map_err(|err| {
+            let _ = self.eval(&format!("close {}", channel));
+            err.get_string().to_string()
+        })?;
+        Ok(channel)
+    }
+}
@@ -0,0 +1,261 @@
+//! Wrappers around Tcl's [child interpreter](https://www.tcl.tk/man/tcl/TclLib/CrtChild.html)
+//! management functions.
+//!
+//! These are the C-level counterparts of `interp create`, `interp alias`,
+//! `interp hide` and `interp expose`, and allow building a sandbox for
+//! untrusted scripts from Rust.
+
+use std::ffi::CStr;
+use std::ffi::CString;
+use std::ffi::c_int;
+use std::ffi::c_void;
+use std::os::raw::c_char;
+
+use crate::Interpreter;
+use crate::Object;
+use crate::RawObject;
+use crate::TclSize;
+use crate::tcl::from_tcl_size;
+use crate::tcl::try_to_tcl_size;
+
+/// A reference to a child or alias target interpreter which notices when the
+/// interpreter is deleted.
+///
+/// Returned by [create_child](Interpreter::create_child),
+/// [get_child](Interpreter::get_child) and [get_alias](Interpreter::get_alias).
+/// The interpreter may be deleted by `interp delete` (or along with its
+/// parent) while the handle exists.  It is preserved (see `Tcl_Preserve`)
+/// until the handle is dropped, so [get](InterpreterHandle::get) can tell.
+#[derive(Debug)]
+pub struct InterpreterHandle {
+    interp: *const Interpreter,
+}
+
+impl InterpreterHandle {
+    fn new(interp: &Interpreter) -> InterpreterHandle {
+        (interp.stubs().Tcl_Preserve)(interp as *const Interpreter as *mut c_void);
+        InterpreterHandle {
+            interp: interp as *const Interpreter,
+        }
+    }
+
+    fn interp(&self) -> &Interpreter {
+        Interpreter::from_raw(self.interp).expect("preserved interpreter corrupted!")
+    }
+
+    /// Gets the interpreter, unless it has been deleted.
+    pub fn get(&self) -> Option<&Interpreter> {
+        let interp = self.interp();
+        if (interp.stubs().Tcl_InterpDeleted)(self.interp) == 0 {
+            Some(interp)
+        } else {
+            None
+        }
+    }
+
+    /// Gets the raw `Tcl_Interp` pointer, e.g., to compare interpreters.
+    ///
+    /// The pointer stays valid (but the interpreter may be deleted) for as
+    /// long as the handle exists.
+    pub fn as_ptr(&self) -> *const Interpreter {
+        self.interp
+    }
+}
+
+impl Clone for InterpreterHandle {
+    fn clone(&self) -> InterpreterHandle {
+        InterpreterHandle::new(self.interp())
+    }
+}
+
+impl Drop for InterpreterHandle {
+    fn drop(&mut self) {
+        (self.interp().stubs().Tcl_Release)(self.interp as *mut c_void);
+    }
+}
+
+/// The definition of an alias, as returned by [get_alias](Interpreter::get_alias).
+#[derive(Debug)]
+pub struct Alias {
+    /// The interpreter in which the aliased command runs.
+    pub target: InterpreterHandle,
+    /// The name of the command the alias invokes.
+    pub target_command: String,
+    /// The words inserted before the arguments given to the alias.
+    pub prefix: Vec<Object>,
+}
+
+impl Interpreter {
+    /// Creates a child interpreter.
+    ///
+    /// `name` is the child's path relative to this interpreter (as used by
+    /// `interp create`).  A safe child only has the commands considered safe
+    /// for untrusted code; other commands may be added back with
+    /// [create_alias](Interpreter::create_alias).
+    ///
+    /// The child is owned by this interpreter and is deleted along with it,
+    /// or earlier by `interp delete`, after which the returned handle no
+    /// longer [gets](InterpreterHandle::get) it.
+    pub fn create_child(&self, name: &str, safe: bool) -> Result<InterpreterHandle, String> {
+        let name =
+            CString::new(name).map_err(|_| "unexpected Nul in interpreter name".to_string())?;
+        let child = (self.stubs().Tcl_CreateChild)(
+            self as *const Interpreter,
+            name.as_ptr(),
+            safe as c_int,
+        );
+        if child.is_null() {
+            return Err(self.get_obj_result().get_string().to_string());
+        }
+
+        let child = Interpreter::from_raw(child).map_err(|err| err.to_string())?;
+        Ok(InterpreterHandle::new(child))
+    }
+
+    /// Gets a child interpreter by its path relative to this interpreter.
+    pub fn get_child(&self, name: &str) -> Option<InterpreterHandle> {
+        let name = CString::new(name).ok()?;
+        let child = (self.stubs().Tcl_GetChild)(self as *const Interpreter, name.as_ptr());
+        Interpreter::from_raw(child)
+            .ok()
+            .map(InterpreterHandle::new)
+    }
+
+    /// Gets the parent of this interpreter, if it is a child.
+    pub fn get_parent(&self) -> Option<&Interpreter> {
+        let parent = (self.stubs().Tcl_GetParent)(self as *const Interpreter);
+        Interpreter::from_raw(parent).ok()
+    }
+
+    /// Checks whether this is a safe interpreter.
+    pub fn is_safe(&self) -> bool {
+        (self.stubs().Tcl_IsSafe)(self as *const Interpreter) != 0
+    }
+
+    /// Creates the command `command` in this interpreter as an alias for
+    /// `target_command` in `target`.
+    ///
+    /// Invoking the alias invokes the target command with `prefix` followed
+    /// by the arguments given to the alias.  This is how a safe interpreter
+    /// is granted controlled access to commands of its parent (`target` is
+    /// typically [get_parent](Interpreter::get_parent) or the interpreter
+    /// that created the child).
+    pub fn create_alias(
+        &self,
+        command: &str,
+        target: &Interpreter,
+        target_command: &str,
+        prefix: &[Object],
+    ) -> Result<(), String> {
+        let command =
+            CString::new(command).map_err(|_| "unexpected Nul in command name".to_string())?;
+        let target_command = CString::new(target_command)
+            .map_err(|_| "unexpected Nul in command name".to_string())?;
+        let objv: Vec<*mut RawObject> = prefix.iter().map(|obj| obj.obj).collect();
+        let objc = try_to_tcl_size(objv.len()).map_err(|err| err.to_string())?;
+
+        let status = (self.stubs().Tcl_CreateAliasObj)(
+            self as *const Interpreter,
+            command.as_ptr(),
+            target as *const Interpreter,
+            target_command.as_ptr(),
+            objc,
+            objv.as_ptr(),
+        );
+        if status == 0 {
+            Ok(())
+        } else {
+            Err(self.get_obj_result().get_string().to_string())
+        }
+    }
+
+    /// Gets the definition of the alias `command` in this interpreter.
+    pub fn get_alias(&self, command: &str) -> Result<Alias, String> {
+        let command =
+            CString::new(command).map_err(|_| "unexpected Nul in command name".to_string())?;
+        let mut target: *const Interpreter = std::ptr::null();
+        let mut target_command: *const c_char = std::ptr::null();
+        let mut objc: TclSize = 0;
+        let mut objv: *mut *mut RawObject = std::ptr::null_mut();
+
+        let status = (self.stubs().Tcl_GetAliasObj)(
+            self as *const Interpreter,
+            command.as_ptr(),
+            &mut target,
+            &mut target_command,
+            &mut objc,
+            &mut objv,
+        );
+        if status != 0 {
+            return Err(self.get_obj_result().get_string().to_string());
+        }
+
+        let target =
+            InterpreterHandle::new(Interpreter::from_raw(target).map_err(|err| err.to_string())?);
+        let target_command = unsafe { CStr::from_ptr(target_command) }
+            .to_str()
+            .expect("invalid command name from Tcl")
+            .to_string();
+        let prefix = if objc == 0 {
+            Vec::new()
+        } else {
+            unsafe { std::slice::from_raw_parts(objv, from_tcl_size(objc)) }
+                .iter()
+                .map(|obj| RawObject::wrap(*obj))
+                .collect()
+        };
+
+        Ok(Alias {
+            target,
+            target_command,
+            prefix,
+        })
+    }
+
+    /// Hides the command `command`.
+    ///
+    /// Hidden commands cannot be invoked by scripts in this interpreter, but
+    /// remain available to its parent through `interp invokehidden`.  The
+    /// command is hidden as `hidden_name` if given, or under the last
+    /// component of its name (hidden names cannot be namespace-qualified).
+    pub fn hide_command(&self, command: &str, hidden_name: Option<&str>) -> Result<(), String> {
+        let tail = command.rsplit("::").next().unwrap_or(command);
+        let hidden_name = CString::new(hidden_name.unwrap_or(tail))
+            .map_err(|_| "unexpected Nul in command name".to_string())?;
+        let command =
+            CString::new(command).map_err(|_| "unexpected Nul in command name".to_string())?;
+
+        let status = (self.stubs().Tcl_HideCommand)(
+            self as *const Interpreter,
+            command.as_ptr(),
+            hidden_name.as_ptr(),
+        );
+        if status == 0 {
+            Ok(())
+        } else {
+            Err(self.get_obj_result().get_string().to_string())
+        }
+    }
+
+    /// Exposes the hidden command `hidden_name` again.
+    ///
+    /// The command becomes available as `command` if given, or under its
+    /// hidden name.
+    pub fn expose_command(&self, hidden_name: &str, command: Option<&str>) -> Result<(), String> {
+        let command = CString::new(command.unwrap_or(hidden_name))
+            .map_err(|_| "unexpected Nul in command name".to_string())?;
+        let hidden_name =
+            CString::new(hidden_name).map_err(|_| "unexpected Nul in command name".to_string())?;
+
+        let status = (self.stubs().Tcl_ExposeCommand)(
+            self as *const Interpreter,
+            hidden_name.as_ptr(),
+            command.as_ptr(),
+        );
+        if status == 0 {
+            Ok(())
+        } else {
+            Err(self.get_obj_result().get_string().to_string())
+        }
+    }
+}
@@ -0,0 +1,117 @@
+//! Support for hosting Tcl from a Rust application.
+//!
+//! Enabled by the `embed` feature, which links the crate against the Tcl
+//! library (`tcl9.0`, or `tcl8.6` with the `tcl86` feature).  If the
+//! library is not in a standard location, `TCL_LIB_DIR` names its
+//! directory.  Extensions loaded by `tclsh` should not enable it; they reach
+//! Tcl through the stubs table instead.
+
+use std::ffi::CString;
+use std::ops::Deref;
+use std::os::raw::c_char;
+use std::sync::Once;
+
+use crate::Error;
+use crate::Interpreter;
+
+#[cfg_attr(feature = "tcl86", link(name = "tcl8.6"))]
+#[cfg_attr(feature = "tcl90", link(name = "tcl9.0"))]
+unsafe extern "C" {
+    fn Tcl_FindExecutable(argv0: *const c_char) -> *const c_char;
+    fn Tcl_CreateInterp() -> *const Interpreter;
+    fn Tcl_DeleteInterp(interp: *const Interpreter);
+}
+
+static FIND_EXECUTABLE: Once = Once::new();
+
+/// A Tcl interpreter created and owned by Rust.
+///
+/// The interpreter is deleted when this value is dropped.  It dereferences
+/// to [Interpreter], so all of its methods are available.  Like every Tcl
+/// interpreter, it may only be used from the thread that created it.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use rtea::*;
+///
+/// let interp = OwnedInterpreter::new().expect("Tcl failed to initialize");
+/// let result = interp.eval("expr {6 * 7}").expect("script failed");
+/// assert_eq!(result.get_string(), "42");
+/// ```
+#[derive(Debug)]
+pub struct OwnedInterpreter {
+    interp: *const Interpreter,
+}
+
+impl OwnedInterpreter {
+    /// Creates an interpreter and runs `Tcl_Init` on it.
+    ///
+    /// `Tcl_Init` sources Tcl's `init.tcl`, which sets up `package require`,
+    /// `auto_load` and the other facilities scripts usually expect.  It fails
+    /// if Tcl's script library cannot be found, in which case the error
+    /// carries Tcl's message.
+    pub fn new() -> Result<OwnedInterpreter, Error> {
+        let owned = OwnedInterpreter::without_init()?;
+        if (owned.stubs().Tcl_Init)(owned.interp) != 0 {
+            return Err(Error::TclError(
+                owned.get_obj_result().get_string().to_string(),
+            ));
+        }
+
+        Ok(owned)
+    }
+
+    /// Creates an interpreter without running `Tcl_Init`.
+    ///
+    /// All built-in commands are available, but the script library (and
+    /// with it `package require`) is not.  This is useful where Tcl is only
+    /// used as a configuration or expression language.
+    pub fn without_init() -> Result<OwnedInterpreter, Error> {
+        FIND_EXECUTABLE.call_once(|| {
+            let argv0 = std::env::args_os()
+                .next()
+                .and_then(|argv0| CString::new(argv0.to_string_lossy().into_owned()).ok());
+            unsafe { Tcl_FindExecutable(argv0.as_ref().map_or(std::ptr::null(), |a| a.as_ptr())) };
+        });
+
+        let raw = unsafe { Tcl_CreateInterp() };
+        if let Err(err) = Interpreter::from_raw(raw) {
+            if !raw.is_null() {
+                unsafe { Tcl_DeleteInterp(raw) };
+            }
+            return Err(err);
+        }
+
+        // From here on, dropping `owned` deletes the interpreter.
+        let owned = OwnedInterpreter { interp: raw };
+        owned.init_global_functions()?;
+
+        Ok(owned)
+    }
+
+    /// Gets the raw `Tcl_Interp` pointer, e.g., to pass it to C code.
+    pub fn as_ptr(&self) -> *const Interpreter {
+        self.interp
+    }
+}
+
+impl Deref for OwnedInterpreter {
+    type Target = Interpreter;
+
+    fn deref(&self) -> &Interpreter {
+        Interpreter::from_raw(self.interp).expect("owned interpreter was validated on creation")
+    }
+}
+
+impl AsRef<Interpreter> for OwnedInterpreter {
+    fn as_ref(&self) -> &Interpreter {
+        self
+    }
+}
+
+impl Drop for OwnedInterpreter {
+    fn drop(&mut self) {
+        (self.stubs().Tcl_DeleteInterp)(self.interp);
+    }
+}
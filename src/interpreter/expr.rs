@@ -0,0 +1,81 @@
+//! Wrappers around Tcl's [expression evaluation](https://www.tcl.tk/man/tcl/TclLib/ExprLongObj.html)
+//! functions.
+//!
+//! Each function takes the expression as an [Object] (without the `expr`
+//! command or surrounding braces).  Tcl caches the compiled form of the
+//! expression in that object, so keeping the `Object` around and evaluating
+//! it repeatedly avoids reparsing it.
+
+use std::ffi::c_double;
+use std::ffi::c_int;
+use std::ffi::c_longlong;
+
+use crate::EvalError;
+use crate::Interpreter;
+use crate::Object;
+use crate::RawObject;
+
+impl Interpreter {
+    /// Evaluates an expression and returns the resulting object.
+    pub fn expr_obj(&self, expr: &Object) -> Result<Object, EvalError> {
+        let mut result: *mut RawObject = std::ptr::null_mut();
+        let status = (self.stubs().Tcl_ExprObj)(self as *const Interpreter, expr.obj, &mut result);
+        if status != 0 {
+            return Err(self.eval_error(status));
+        }
+
+        // Tcl hands over a reference to the result, which `Object` now owns.
+        Ok(Object { obj: result })
+    }
+
+    /// Evaluates an expression as a boolean.
+    ///
+    /// Any value Tcl accepts as a boolean (`1`, `yes`, `true`, ...) or any
+    /// numeric value is accepted as the result.
+    pub fn expr_bool(&self, expr: &Object) -> Result<bool, EvalError> {
+        let mut result: c_int = 0;
+        let status =
+            (self.stubs().Tcl_ExprBooleanObj)(self as *const Interpreter, expr.obj, &mut result);
+        if status != 0 {
+            return Err(self.eval_error(status));
+        }
+
+        Ok(result != 0)
+    }
+
+    /// Evaluates an expression as a 64-bit integer.
+    ///
+    /// Rather than `Tcl_ExprLongObj` (whose result is a C `long` and only 32
+    /// bits wide on some platforms), this evaluates the expression with
+    /// `Tcl_ExprObj` and converts the result with `Tcl_GetWideIntFromObj`.
+    /// Results that are not integers (including floating point values) are
+    /// an error.
+    pub fn expr_i64(&self, expr: &Object) -> Result<i64, EvalError> {
+        let result = self.expr_obj(expr)?;
+        let mut value: c_longlong = 0;
+        let status = (self.stubs().Tcl_GetWideIntFromObj)(
+            self as *const Interpreter,
+            result.obj,
+            &mut value,
+        );
+        if status != 0 {
+            return Err(self.eval_error(status));
+        }
+
+        Ok(value)
+    }
+
+    /// Evaluates an expression as a floating point value.
+    ///
+    /// Integer results are converted to floating point.
+    pub fn expr_f64(&self, expr: &Object) -> Result<f64, EvalError> {
+        let mut result: c_double = 0.0;
+        let status =
+            (self.stubs().Tcl_ExprDoubleObj)(self as *const Interpreter, expr.obj, &mut result);
+        if status != 0 {
+            return Err(self.eval_error(status));
+        }
+
+        Ok(result)
+    }
+}
@@ -0,0 +1,417 @@
+//! Wrappers around Tcl's [interpreter deletion](https://www.tcl.tk/man/tcl/TclLib/CallDel.html)
+//! and [exit](https://www.tcl.tk/man/tcl/TclLib/Exit.html) callbacks.
+//!
+//! Each handler is owned by a Rust guard object.  Dropping the guard removes
+//! the handler, unless it has already run or the guard was detached.
+//! Handlers registered by an extension's `*_Init` function are also removed
+//! when the extension is unloaded, even if their guards were detached or
+//! leaked.
+
+use std::any::Any;
+use std::any::TypeId;
+use std::cell::Cell;
+use std::cell::RefCell;
+use std::ffi::CString;
+use std::ffi::c_void;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+
+use super::stubs::Tcl_ExitProc;
+use super::unload;
+use crate::Interpreter;
+
+type DeleteProc = dyn FnOnce(&Interpreter);
+
+/// What rtea tears down when an interpreter is deleted.
+///
+/// Tcl runs its deletion callbacks (including the ones for AssocData) in
+/// no particular order, so the deletion handlers and the per-interpreter
+/// state share a single AssocData entry that tears them down in order.
+#[derive(Default)]
+pub(super) struct Teardown {
+    /// The deletion handlers, in the order they were registered.
+    handlers: RefCell<Vec<Rc<DeleteHandlerState>>>,
+    /// The per-interpreter state, in the order it was stored.
+    pub(super) states: RefCell<Vec<(TypeId, Box<dyn Any>)>>,
+}
+
+/// Builds the AssocData key of the teardown.
+///
+/// The `TypeId` keeps extensions bundling different builds of rtea apart.
+fn teardown_key() -> CString {
+    CString::new(format!("rtea::teardown::{:?}", TypeId::of::<Teardown>()))
+        .expect("type ids have no Nul")
+}
+
+extern "C" fn free_teardown(teardown: *mut c_void, i: *const Interpreter) {
+    let interp = Interpreter::from_raw(i).expect("Tcl passed bad interpreter");
+    let key = teardown_key();
+
+    // Tcl has already detached the interpreter's AssocData, so the teardown
+    // is put back (without a delete proc) while the handlers run, so they
+    // can still use the state and register further handlers.
+    (interp.stubs().Tcl_SetAssocData)(i, key.as_ptr(), None, teardown);
+    let teardown = unsafe { Box::from_raw(teardown as *mut Teardown) };
+    loop {
+        let handlers = std::mem::take(&mut *teardown.handlers.borrow_mut());
+        if handlers.is_empty() {
+            break;
+        }
+        for handler in handlers {
+            handler.run(interp);
+        }
+    }
+    (interp.stubs().Tcl_DeleteAssocData)(i, key.as_ptr());
+
+    // Newest first, so that state may still refer to older state while it
+    // is dropped.
+    loop {
+        let state = teardown.states.borrow_mut().pop();
+        match state {
+            Some(state) => drop(state),
+            None => break,
+        }
+    }
+}
+
+impl Interpreter {
+    /// Gets the interpreter's teardown, creating it if needed.
+    pub(super) fn teardown(&self) -> &Teardown {
+        if let Some(teardown) = self.existing_teardown() {
+            return teardown;
+        }
+
+        let key = teardown_key();
+        let teardown = Box::into_raw(Box::<Teardown>::default());
+        (self.stubs().Tcl_SetAssocData)(
+            self as *const Interpreter,
+            key.as_ptr(),
+            Some(free_teardown),
+            teardown as *mut c_void,
+        );
+        unsafe { &*teardown }
+    }
+
+    /// Gets the interpreter's teardown, if it has one.
+    pub(super) fn existing_teardown(&self) -> Option<&Teardown> {
+        let key = teardown_key();
+        let teardown = (self.stubs().Tcl_GetAssocData)(
+            self as *const Interpreter,
+            key.as_ptr(),
+            std::ptr::null_mut(),
+        );
+        unsafe { (teardown as *const Teardown).as_ref() }
+    }
+}
+
+struct DeleteHandlerState {
+    interp: *const Interpreter,
+    active: Cell<bool>,
+    proc: RefCell<Option<Box<DeleteProc>>>,
+}
+
+/// A deletion handler registered with [call_when_deleted](Interpreter::call_when_deleted).
+///
+/// Dropping the guard removes the handler, unless it has been
+/// [detached](DeleteHandler::detach).
+pub struct DeleteHandler {
+    state: Rc<DeleteHandlerState>,
+    detached: bool,
+}
+
+impl DeleteHandler {
+    /// Checks whether the handler is still waiting for the interpreter to be
+    /// deleted.
+    pub fn is_active(&self) -> bool {
+        self.state.active.get()
+    }
+
+    /// Releases the guard without removing the handler.
+    pub fn detach(mut self) {
+        self.detached = true;
+    }
+}
+
+impl DeleteHandlerState {
+    /// Removes the handler from the interpreter, unless it already ran.
+    fn remove(self: &Rc<Self>) {
+        if !self.active.replace(false) {
+            return;
+        }
+
+        // The interpreter is still alive, as the handler has not run.
+        if let Ok(interp) = Interpreter::from_raw(self.interp)
+            && let Some(teardown) = interp.existing_teardown()
+        {
+            teardown
+                .handlers
+                .borrow_mut()
+                .retain(|handler| !Rc::ptr_eq(handler, self));
+        }
+        let proc = self.proc.borrow_mut().take();
+        drop(proc);
+    }
+
+    fn run(&self, interp: &Interpreter) {
+        if !self.active.replace(false) {
+            return;
+        }
+
+        let proc = self.proc.borrow_mut().take();
+        if let Some(proc) = proc {
+            proc(interp);
+        }
+    }
+}
+
+impl Drop for DeleteHandler {
+    fn drop(&mut self) {
+        if !self.detached {
+            self.state.remove();
+        }
+    }
+}
+
+type ExitProc = dyn FnOnce() + Send;
+
+struct ExitHandlerState {
+    active: AtomicBool,
+    proc: Mutex<Option<Box<ExitProc>>>,
+    delete: extern "C" fn(Option<Tcl_ExitProc>, *mut c_void),
+}
+
+/// A process exit handler registered with
+/// [create_exit_handler](Interpreter::create_exit_handler).
+///
+/// Dropping the guard removes the handler, unless it has been
+/// [detached](ExitHandler::detach).  The guard may be sent to (and dropped
+/// on) any thread.
+pub struct ExitHandler {
+    state: Arc<ExitHandlerState>,
+    detached: bool,
+}
+
+impl ExitHandler {
+    /// Checks whether the handler is still waiting for Tcl to exit.
+    pub fn is_active(&self) -> bool {
+        self.state.active.load(Ordering::Acquire)
+    }
+
+    /// Releases the guard without removing the handler.
+    pub fn detach(mut self) {
+        self.detached = true;
+    }
+}
+
+impl ExitHandlerState {
+    /// Removes the handler, unless it already ran.
+    ///
+    /// Tcl's reference to the state is only ever released by
+    /// `exit_handler_proc`, which may be running on another thread at the
+    /// same time.  Once the handler is removed, Tcl never calls it, so the
+    /// state (without the closure, which is dropped here) is leaked.
+    fn remove(self: &Arc<Self>) {
+        if !self.active.swap(false, Ordering::AcqRel) {
+            return;
+        }
+
+        (self.delete)(Some(exit_handler_proc), Arc::as_ptr(self) as *mut c_void);
+        let proc = self.proc.lock().ok().and_then(|mut proc| proc.take());
+        drop(proc);
+    }
+}
+
+impl Drop for ExitHandler {
+    fn drop(&mut self) {
+        if !self.detached {
+            self.state.remove();
+        }
+    }
+}
+
+extern "C" fn exit_handler_proc(state: *mut c_void) {
+    // This takes over Tcl's reference, which nothing else releases.
+    let state = unsafe { Arc::from_raw(state as *const ExitHandlerState) };
+    if !state.active.swap(false, Ordering::AcqRel) {
+        // The handler is being removed concurrently.
+        return;
+    }
+
+    let proc = state.proc.lock().ok().and_then(|mut proc| proc.take());
+    if let Some(proc) = proc {
+        proc();
+    }
+}
+
+struct ThreadExitHandlerState {
+    active: Cell<bool>,
+    proc: RefCell<Option<Box<dyn FnOnce()>>>,
+    delete: extern "C" fn(Option<Tcl_ExitProc>, *mut c_void),
+}
+
+/// A thread exit handler registered with
+/// [create_thread_exit_handler](Interpreter::create_thread_exit_handler).
+///
+/// Dropping the guard removes the handler, unless it has been
+/// [detached](ThreadExitHandler::detach).
+pub struct ThreadExitHandler {
+    state: Rc<ThreadExitHandlerState>,
+    detached: bool,
+}
+
+impl ThreadExitHandler {
+    /// Checks whether the handler is still waiting for the thread to exit.
+    pub fn is_active(&self) -> bool {
+        self.state.active.get()
+    }
+
+    /// Releases the guard without removing the handler.
+    pub fn detach(mut self) {
+        self.detached = true;
+    }
+}
+
+impl ThreadExitHandlerState {
+    /// Removes the handler, unless it already ran.
+    fn remove(self: &Rc<Self>) {
+        if !self.active.replace(false) {
+            return;
+        }
+
+        let state = Rc::as_ptr(self);
+        (self.delete)(Some(thread_exit_handler_proc), state as *mut c_void);
+
+        // Tcl no longer references the state so its reference can be released.
+        unsafe { drop(Rc::from_raw(state)) };
+    }
+}
+
+impl Drop for ThreadExitHandler {
+    fn drop(&mut self) {
+        if !self.detached {
+            self.state.remove();
+        }
+    }
+}
+
+extern "C" fn thread_exit_handler_proc(state: *mut c_void) {
+    let state = unsafe { Rc::from_raw(state as *const ThreadExitHandlerState) };
+    state.active.set(false);
+    let proc = state.proc.borrow_mut().take();
+    if let Some(proc) = proc {
+        proc();
+    }
+}
+
+impl Interpreter {
+    /// Registers a closure to run when the interpreter is deleted.
+    ///
+    /// This works like [Tcl_CallWhenDeleted](https://www.tcl.tk/man/tcl/TclLib/CallDel.html),
+    /// but in a defined order.
+    ///
+    /// Tcl deletes an interpreter's commands (running their delete traces
+    /// and dropping [StatefulCommand](crate::StatefulCommand) data) and
+    /// variables first.  Then the deletion handlers run, in the order they
+    /// were registered, and only after all of them have run is the
+    /// per-interpreter [state](Interpreter::state) dropped.  The closure may
+    /// still use the interpreter, e.g., to read the state.
+    ///
+    /// Tcl does not delete interpreters when the process exits, so cleanup
+    /// that must happen in either case also needs an
+    /// [exit handler](Interpreter::create_exit_handler).
+    pub fn call_when_deleted<F>(&self, proc: F) -> DeleteHandler
+    where
+        F: FnOnce(&Interpreter) + 'static,
+    {
+        let state = Rc::new(DeleteHandlerState {
+            interp: self as *const Interpreter,
+            active: Cell::new(true),
+            proc: RefCell::new(Some(Box::new(proc))),
+        });
+
+        self.teardown().handlers.borrow_mut().push(state.clone());
+
+        let weak = Rc::downgrade(&state);
+        unload::record(self, move |_| {
+            if let Some(state) = weak.upgrade() {
+                state.remove();
+            }
+        });
+
+        DeleteHandler {
+            state,
+            detached: false,
+        }
+    }
+
+    /// Registers a closure to run when Tcl exits.
+    ///
+    /// This wraps [Tcl_CreateExitHandler](https://www.tcl.tk/man/tcl/TclLib/Exit.html).
+    /// The handler is process-wide and runs (on whichever thread calls `exit`
+    /// or `Tcl_Finalize`) before the process exits, after the handlers
+    /// created later than it.
+    pub fn create_exit_handler<F>(&self, proc: F) -> ExitHandler
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        let state = Arc::new(ExitHandlerState {
+            active: AtomicBool::new(true),
+            proc: Mutex::new(Some(Box::new(proc))),
+            delete: self.stubs().Tcl_DeleteExitHandler,
+        });
+
+        (self.stubs().Tcl_CreateExitHandler)(
+            Some(exit_handler_proc),
+            Arc::into_raw(state.clone()) as *mut c_void,
+        );
+
+        let weak = Arc::downgrade(&state);
+        unload::record(self, move |_| {
+            if let Some(state) = weak.upgrade() {
+                state.remove();
+            }
+        });
+
+        ExitHandler {
+            state,
+            detached: false,
+        }
+    }
+
+    /// Registers a closure to run when the current thread exits.
+    ///
+    /// This wraps [Tcl_CreateThreadExitHandler](https://www.tcl.tk/man/tcl/TclLib/Exit.html).
+    /// The handler runs when Tcl is finalized for the current thread (e.g.,
+    /// when a thread created by the `Thread` package ends, or on exit for the
+    /// main thread), after the handlers created later than it.
+    pub fn create_thread_exit_handler<F>(&self, proc: F) -> ThreadExitHandler
+    where
+        F: FnOnce() + 'static,
+    {
+        let state = Rc::new(ThreadExitHandlerState {
+            active: Cell::new(true),
+            proc: RefCell::new(Some(Box::new(proc))),
+            delete: self.stubs().Tcl_DeleteThreadExitHandler,
+        });
+
+        (self.stubs().Tcl_CreateThreadExitHandler)(
+            Some(thread_exit_handler_proc),
+            Rc::into_raw(state.clone()) as *mut c_void,
+        );
+
+        let weak = Rc::downgrade(&state);
+        unload::record(self, move |_| {
+            if let Some(state) = weak.upgrade() {
+                state.remove();
+            }
+        });
+
+        ThreadExitHandler {
+            state,
+            detached: false,
+        }
+    }
+}
@@ -0,0 +1,281 @@
+//! Wrappers around Tcl's [resource limits](https://www.tcl.tk/man/tcl/TclLib/Limit.html).
+//!
+//! These are the C-level counterpart of `interp limit` and allow bounding
+//! the number of commands and the wall-clock time a script may use.
+
+use std::cell::Cell;
+use std::ffi::c_int;
+use std::ffi::c_long;
+#[cfg(feature = "tcl90")]
+use std::ffi::c_longlong;
+use std::ffi::c_void;
+use std::rc::Rc;
+use std::time::Duration;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use super::unload;
+use crate::EvalError;
+use crate::Interpreter;
+use crate::tcl::saturating_tcl_size;
+
+/// The kinds of resource limit Tcl supports.
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LimitType {
+    /// Limits the total number of commands executed by the interpreter.
+    Commands = 0x01,
+    /// Limits evaluation to a wall-clock deadline.
+    Time = 0x02,
+}
+
+/// Mirrors Tcl 9's `Tcl_Time`.
+#[cfg(feature = "tcl90")]
+#[repr(C)]
+#[derive(Debug, Default)]
+struct TclTime {
+    sec: c_longlong,
+    usec: c_long,
+}
+
+/// Mirrors Tcl 8.6's `Tcl_Time`, whose seconds are a `long`.
+#[cfg(feature = "tcl86")]
+#[repr(C)]
+#[derive(Debug, Default)]
+struct TclTime {
+    sec: c_long,
+    usec: c_long,
+}
+
+/// Access to the resource limits of an interpreter.
+///
+/// Created by [limits](Interpreter::limits).  Setting a limit's value does
+/// not enable it; use [enable](Limits::enable) for that.  Once an enabled
+/// limit is exceeded, the handlers registered with
+/// [add_handler](Limits::add_handler) run and may raise the limit.  If they
+/// do not, the script in progress fails with a `TCL LIMIT` error code and
+/// the interpreter refuses to run further commands until the limit is
+/// raised or disabled.
+pub struct Limits<'a> {
+    interp: &'a Interpreter,
+}
+
+impl Limits<'_> {
+    /// Enables checking of the given limit.
+    pub fn enable(&self, limit: LimitType) {
+        (self.interp.stubs().Tcl_LimitTypeSet)(self.interp as *const Interpreter, limit as c_int);
+    }
+
+    /// Disables checking of the given limit.
+    pub fn disable(&self, limit: LimitType) {
+        (self.interp.stubs().Tcl_LimitTypeReset)(self.interp as *const Interpreter, limit as c_int);
+    }
+
+    /// Checks whether the given limit is enabled.
+    pub fn is_enabled(&self, limit: LimitType) -> bool {
+        (self.interp.stubs().Tcl_LimitTypeEnabled)(
+            self.interp as *const Interpreter,
+            limit as c_int,
+        ) != 0
+    }
+
+    /// Checks whether the given limit has been exceeded.
+    pub fn is_exceeded(&self, limit: LimitType) -> bool {
+        (self.interp.stubs().Tcl_LimitTypeExceeded)(
+            self.interp as *const Interpreter,
+            limit as c_int,
+        ) != 0
+    }
+
+    /// Sets the command limit.
+    ///
+    /// The limit applies to the interpreter's total command count (as
+    /// reported by `info cmdcount`), not to the commands run from now on.
+    /// Counts beyond what Tcl supports are capped at the largest one it
+    /// does.
+    pub fn set_commands(&self, count: usize) {
+        (self.interp.stubs().Tcl_LimitSetCommands)(
+            self.interp as *const Interpreter,
+            saturating_tcl_size(count),
+        );
+    }
+
+    /// Gets the command limit.
+    pub fn commands(&self) -> usize {
+        (self.interp.stubs().Tcl_LimitGetCommands)(self.interp as *const Interpreter) as usize
+    }
+
+    /// Sets the time limit to the given deadline.
+    pub fn set_time(&self, deadline: SystemTime) {
+        let since_epoch = deadline.duration_since(UNIX_EPOCH).unwrap_or_default();
+        let mut time = TclTime {
+            sec: since_epoch.as_secs() as _,
+            usec: since_epoch.subsec_micros() as c_long,
+        };
+        (self.interp.stubs().Tcl_LimitSetTime)(
+            self.interp as *const Interpreter,
+            (&mut time as *mut TclTime).cast(),
+        );
+    }
+
+    /// Gets the time limit's deadline.
+    pub fn time(&self) -> SystemTime {
+        let mut time = TclTime::default();
+        (self.interp.stubs().Tcl_LimitGetTime)(
+            self.interp as *const Interpreter,
+            (&mut time as *mut TclTime).cast(),
+        );
+        UNIX_EPOCH
+            + Duration::from_secs(time.sec.max(0) as u64)
+            + Duration::from_micros(time.usec.max(0) as u64)
+    }
+
+    /// Sets how often (in checks) the given limit is tested.
+    ///
+    /// A granularity of `n` checks the limit on every `n`th opportunity,
+    /// trading precision for speed.  The default is 1 for commands and 10
+    /// for time.
+    pub fn set_granularity(&self, limit: LimitType, granularity: u32) {
+        (self.interp.stubs().Tcl_LimitSetGranularity)(
+            self.interp as *const Interpreter,
+            limit as c_int,
+            granularity.max(1) as c_int,
+        );
+    }
+
+    /// Gets how often (in checks) the given limit is tested.
+    pub fn granularity(&self, limit: LimitType) -> u32 {
+        (self.interp.stubs().Tcl_LimitGetGranularity)(
+            self.interp as *const Interpreter,
+            limit as c_int,
+        ) as u32
+    }
+
+    /// Registers a closure to run when the given limit is exceeded.
+    ///
+    /// The handler may extend the limit (e.g., with
+    /// [set_commands](Limits::set_commands)) to let the script continue, or
+    /// leave it untouched to enforce it.  The handler stays registered until
+    /// the returned guard is dropped or the interpreter is deleted.
+    pub fn add_handler<F>(&self, limit: LimitType, handler: F) -> LimitHandler
+    where
+        F: Fn(&Interpreter) + 'static,
+    {
+        let state = Rc::new(LimitHandlerState {
+            interp: self.interp as *const Interpreter,
+            limit,
+            active: Cell::new(true),
+            handler: Box::new(handler),
+        });
+
+        (self.interp.stubs().Tcl_LimitAddHandler)(
+            self.interp as *const Interpreter,
+            limit as c_int,
+            Some(limit_handler_proc),
+            Rc::into_raw(state.clone()) as *mut c_void,
+            Some(limit_handler_delete),
+        );
+
+        let weak = Rc::downgrade(&state);
+        unload::record(self.interp, move |_| {
+            if let Some(state) = weak.upgrade() {
+                state.remove();
+            }
+        });
+        LimitHandler { state }
+    }
+}
+
+struct LimitHandlerState {
+    interp: *const Interpreter,
+    limit: LimitType,
+    active: Cell<bool>,
+    handler: Box<dyn Fn(&Interpreter)>,
+}
+
+/// A limit handler registered with [add_handler](Limits::add_handler).
+///
+/// Dropping the guard removes the handler.  If the interpreter was deleted
+/// first, dropping the guard only releases the Rust closure.
+pub struct LimitHandler {
+    state: Rc<LimitHandlerState>,
+}
+
+impl LimitHandler {
+    /// Checks whether the handler is still registered with the interpreter.
+    pub fn is_active(&self) -> bool {
+        self.state.active.get()
+    }
+}
+
+impl LimitHandlerState {
+    /// Removes the handler from the interpreter, unless Tcl already did.
+    fn remove(self: &Rc<Self>) {
+        if !self.active.get() {
+            return;
+        }
+
+        // Tcl invokes `limit_handler_delete` (possibly later, if the handler
+        // is running), which releases the reference Tcl holds.
+        if let Ok(interp) = Interpreter::from_raw(self.interp) {
+            (interp.stubs().Tcl_LimitRemoveHandler)(
+                interp as *const Interpreter,
+                self.limit as c_int,
+                Some(limit_handler_proc),
+                Rc::as_ptr(self) as *mut c_void,
+            );
+        }
+    }
+}
+
+impl Drop for LimitHandler {
+    fn drop(&mut self) {
+        self.state.remove();
+    }
+}
+
+extern "C" fn limit_handler_proc(state: *mut c_void, i: *const Interpreter) {
+    let interp = Interpreter::from_raw(i).expect("Tcl passed bad interpreter");
+    let state =
+        unsafe { (state as *const LimitHandlerState).as_ref() }.expect("limit handler corrupted!");
+    (state.handler)(interp);
+}
+
+extern "C" fn limit_handler_delete(state: *mut c_void) {
+    let state = unsafe { Rc::from_raw(state as *const LimitHandlerState) };
+    state.active.set(false);
+}
+
+impl Interpreter {
+    /// Gets access to the interpreter's resource limits.
+    pub fn limits(&self) -> Limits<'_> {
+        Limits { interp: self }
+    }
+
+    /// Checks whether an enabled resource limit has been exceeded.
+    ///
+    /// Long-running commands implemented in Rust should call this
+    /// periodically (the limit's granularity is honored, so calling it often
+    /// is cheap).  Limit handlers run as part of the check.  If a limit
+    /// remains exceeded, the returned error carries Tcl's message and the
+    /// `TCL LIMIT` error code, and should be returned from the command.
+    pub fn check_limits(&self) -> Result<(), EvalError> {
+        let interp = self as *const Interpreter;
+        if (self.stubs().Tcl_LimitReady)(interp) == 0 {
+            return Ok(());
+        }
+
+        let status = (self.stubs().Tcl_LimitCheck)(interp);
+        if status == 0 {
+            Ok(())
+        } else {
+            Err(self.eval_error(status))
+        }
+    }
+
+    /// Checks whether any resource limit is currently exceeded, without
+    /// running limit handlers.
+    pub fn limit_exceeded(&self) -> bool {
+        (self.stubs().Tcl_LimitExceeded)(self as *const Interpreter) != 0
+    }
+}
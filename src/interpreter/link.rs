@@ -0,0 +1,413 @@
+//! Wrappers around Tcl's [linked variables](https://www.tcl.tk/man/tcl/TclLib/LinkVar.html).
+//!
+//! A linked variable is a Tcl variable whose value lives in memory owned by
+//! Rust.  Reads from Tcl see the current Rust value and (unless the link is
+//! read-only) writes from Tcl are stored straight into the Rust side.
+//! Variables linked by an extension's `*_Init` function are unlinked when
+//! the extension is unloaded, even if their handles were leaked.
+
+use std::cell::Cell;
+use std::ffi::CStr;
+use std::ffi::CString;
+use std::ffi::c_double;
+use std::ffi::c_int;
+use std::ffi::c_longlong;
+use std::ffi::c_void;
+use std::os::raw::c_char;
+use std::rc::Rc;
+
+use super::unload;
+use crate::Interpreter;
+use crate::tcl::*;
+
+const TCL_LINK_INT: c_int = 1;
+const TCL_LINK_DOUBLE: c_int = 2;
+const TCL_LINK_BOOLEAN: c_int = 3;
+const TCL_LINK_STRING: c_int = 4;
+const TCL_LINK_WIDE_INT: c_int = 5;
+const TCL_LINK_READ_ONLY: c_int = 0x80;
+
+/// Controls whether Tcl scripts may write to a linked variable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkMode {
+    /// Scripts may read and write the variable.
+    ReadWrite,
+    /// Scripts may only read the variable; writes fail with an error.
+    ReadOnly,
+}
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// Rust types which can back a Tcl linked variable.
+///
+/// This trait is sealed and implemented for `i32`, `i64`, `f64`, `bool` and
+/// `String`, which correspond to the `TCL_LINK_INT`, `TCL_LINK_WIDE_INT`,
+/// `TCL_LINK_DOUBLE`, `TCL_LINK_BOOLEAN` and `TCL_LINK_STRING` link types.
+pub trait LinkValue: private::Sealed + Sized {
+    /// The C representation Tcl reads from and writes to.
+    #[doc(hidden)]
+    type Storage;
+
+    #[doc(hidden)]
+    const LINK_TYPE: c_int;
+
+    #[doc(hidden)]
+    fn into_storage(self) -> Self::Storage;
+
+    #[doc(hidden)]
+    fn from_storage(storage: &Self::Storage) -> Self;
+
+    #[doc(hidden)]
+    fn release(_storage: &mut Self::Storage) {}
+}
+
+/// Linked variable types which may also be linked as arrays.
+///
+/// See [link_array](Interpreter::link_array).
+#[cfg(feature = "tcl90")]
+pub trait LinkElement: LinkValue + Copy {}
+
+macro_rules! link_value_identity {
+    ($ty:ty, $storage:ty, $link_type:expr) => {
+        impl private::Sealed for $ty {}
+
+        impl LinkValue for $ty {
+            type Storage = $storage;
+
+            const LINK_TYPE: c_int = $link_type;
+
+            fn into_storage(self) -> $storage {
+                self as $storage
+            }
+
+            fn from_storage(storage: &$storage) -> $ty {
+                *storage as $ty
+            }
+        }
+
+        #[cfg(feature = "tcl90")]
+        impl LinkElement for $ty {}
+    };
+}
+
+link_value_identity!(i32, c_int, TCL_LINK_INT);
+link_value_identity!(i64, c_longlong, TCL_LINK_WIDE_INT);
+link_value_identity!(f64, c_double, TCL_LINK_DOUBLE);
+
+impl private::Sealed for bool {}
+
+impl LinkValue for bool {
+    type Storage = c_int;
+
+    const LINK_TYPE: c_int = TCL_LINK_BOOLEAN;
+
+    fn into_storage(self) -> c_int {
+        self as c_int
+    }
+
+    fn from_storage(storage: &c_int) -> bool {
+        *storage != 0
+    }
+}
+
+#[cfg(feature = "tcl90")]
+impl LinkElement for bool {}
+
+impl private::Sealed for String {}
+
+impl LinkValue for String {
+    // Tcl frees and reallocates the string with `Tcl_Free`/`Tcl_Alloc`
+    // whenever a script writes the variable.
+    type Storage = *mut c_char;
+
+    const LINK_TYPE: c_int = TCL_LINK_STRING;
+
+    fn into_storage(self) -> *mut c_char {
+        tcl_string(&self).0
+    }
+
+    fn from_storage(storage: &*mut c_char) -> String {
+        if storage.is_null() {
+            String::new()
+        } else {
+            unsafe { CStr::from_ptr(*storage) }
+                .to_str()
+                .expect("TCL guarantees strings are valid UTF-8")
+                .to_string()
+        }
+    }
+
+    fn release(storage: &mut *mut c_char) {
+        if !storage.is_null() {
+            global_functions().free(*storage as *mut c_void);
+            *storage = std::ptr::null_mut();
+        }
+    }
+}
+
+/// The Tcl side of a link: the interpreter and variable name.
+///
+/// The interpreter is preserved (see `Tcl_Preserve`) for as long as the link
+/// exists so that it can be checked for deletion when the link is dropped.
+struct Link {
+    interp: *const Interpreter,
+    name: CString,
+    /// Whether Tcl still has the variable linked, shared with the unload
+    /// registry.
+    linked: Rc<Cell<bool>>,
+}
+
+impl Link {
+    fn new(interp: &Interpreter, name: &str) -> Result<Link, String> {
+        let name = CString::new(name).map_err(|_| "unexpected Nul in variable name".to_string())?;
+        (interp.stubs().Tcl_Preserve)(interp as *const Interpreter as *mut c_void);
+        Ok(Link {
+            interp: interp as *const Interpreter,
+            name,
+            linked: Rc::new(Cell::new(false)),
+        })
+    }
+
+    fn interp(&self) -> &Interpreter {
+        Interpreter::from_raw(self.interp).expect("preserved interpreter corrupted!")
+    }
+
+    fn attached(&self) -> Option<&Interpreter> {
+        let interp = self.interp();
+        if (interp.stubs().Tcl_InterpDeleted)(self.interp) == 0 {
+            Some(interp)
+        } else {
+            None
+        }
+    }
+
+    /// Checks the status of linking the variable and, on success, records
+    /// how to unlink it when the extension is unloaded.
+    fn check(&self, status: c_int) -> Result<(), String> {
+        if status != 0 {
+            return Err(self.interp().get_obj_result().get_string().to_string());
+        }
+
+        self.linked.set(true);
+        let linked = Rc::downgrade(&self.linked);
+        let name = self.name.clone();
+        unload::record(self.interp(), move |interp| {
+            if let Some(linked) = linked.upgrade()
+                && linked.replace(false)
+            {
+                (interp.stubs().Tcl_UnlinkVar)(interp as *const Interpreter, name.as_ptr());
+            }
+        });
+        Ok(())
+    }
+
+    fn update(&self) {
+        if let Some(interp) = self.attached() {
+            (interp.stubs().Tcl_UpdateLinkedVar)(self.interp, self.name.as_ptr());
+        }
+    }
+}
+
+impl Drop for Link {
+    fn drop(&mut self) {
+        if self.linked.replace(false)
+            && let Some(interp) = self.attached()
+        {
+            (interp.stubs().Tcl_UnlinkVar)(self.interp, self.name.as_ptr());
+        }
+        (self.interp().stubs().Tcl_Release)(self.interp as *mut c_void);
+    }
+}
+
+/// Rust-owned storage for a single linked value, freed once unlinked.
+struct LinkCell<T: LinkValue>(*mut T::Storage);
+
+impl<T: LinkValue> Drop for LinkCell<T> {
+    fn drop(&mut self) {
+        let mut storage = unsafe { Box::from_raw(self.0) };
+        T::release(&mut storage);
+    }
+}
+
+/// Rust-owned storage for a linked buffer, freed once unlinked.
+#[cfg(feature = "tcl90")]
+struct LinkCells<T: LinkElement>(*mut [T::Storage]);
+
+#[cfg(feature = "tcl90")]
+impl<T: LinkElement> Drop for LinkCells<T> {
+    fn drop(&mut self) {
+        unsafe { drop(Box::from_raw(self.0)) };
+    }
+}
+
+/// A Tcl variable bound to a Rust-owned value.
+///
+/// Created by [link_var](Interpreter::link_var).  The variable is unlinked
+/// when this handle is dropped; if the interpreter was deleted first, the
+/// handle simply releases its storage.
+pub struct LinkedVar<T: LinkValue> {
+    // Fields drop in order, so Tcl is unlinked before the storage is freed.
+    link: Link,
+    cell: LinkCell<T>,
+}
+
+impl<T: LinkValue> LinkedVar<T> {
+    /// Gets the current value, including any writes made by Tcl scripts.
+    pub fn get(&self) -> T {
+        T::from_storage(unsafe { &*self.cell.0 })
+    }
+
+    /// Sets the value and notifies Tcl (and any traces on the variable).
+    pub fn set(&self, value: T) {
+        unsafe {
+            T::release(&mut *self.cell.0);
+            *self.cell.0 = value.into_storage();
+        }
+        self.link.update();
+    }
+
+    /// Gets the name of the linked Tcl variable.
+    pub fn name(&self) -> &str {
+        self.link
+            .name
+            .to_str()
+            .expect("name was created from a &str")
+    }
+}
+
+/// A Tcl variable bound to a Rust-owned buffer of values.
+///
+/// Created by [link_array](Interpreter::link_array).  From Tcl the variable
+/// is a list with one element per slot of the buffer.
+#[cfg(feature = "tcl90")]
+pub struct LinkedArray<T: LinkElement> {
+    link: Link,
+    cells: LinkCells<T>,
+}
+
+#[cfg(feature = "tcl90")]
+impl<T: LinkElement> LinkedArray<T> {
+    /// Gets the number of elements in the linked buffer.
+    pub fn len(&self) -> usize {
+        self.cells.0.len()
+    }
+
+    /// Checks whether the linked buffer is empty.
+    pub fn is_empty(&self) -> bool {
+        self.cells.0.is_empty()
+    }
+
+    /// Gets the element at `index`, including any writes made by Tcl scripts.
+    pub fn get(&self, index: usize) -> Option<T> {
+        unsafe { &*self.cells.0 }.get(index).map(T::from_storage)
+    }
+
+    /// Copies the current contents of the buffer into a `Vec`.
+    pub fn to_vec(&self) -> Vec<T> {
+        unsafe { &*self.cells.0 }
+            .iter()
+            .map(T::from_storage)
+            .collect()
+    }
+
+    /// Sets the element at `index` and notifies Tcl.
+    ///
+    /// Returns `false` (without changing anything) if `index` is out of
+    /// bounds.
+    pub fn set(&self, index: usize, value: T) -> bool {
+        match unsafe { &mut *self.cells.0 }.get_mut(index) {
+            Some(cell) => {
+                *cell = value.into_storage();
+                self.link.update();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Gets the name of the linked Tcl variable.
+    pub fn name(&self) -> &str {
+        self.link
+            .name
+            .to_str()
+            .expect("name was created from a &str")
+    }
+}
+
+impl Interpreter {
+    /// Links the Tcl variable `name` to a Rust-owned value.
+    ///
+    /// This wraps [Tcl_LinkVar](https://www.tcl.tk/man/tcl/TclLib/LinkVar.html).
+    /// The variable is created (or overwritten) with `value`.  Afterward,
+    /// Tcl reads return the current Rust value and, for
+    /// [LinkMode::ReadWrite], Tcl writes are validated against the type `T`
+    /// and stored on the Rust side.
+    pub fn link_var<T: LinkValue>(
+        &self,
+        name: &str,
+        value: T,
+        mode: LinkMode,
+    ) -> Result<LinkedVar<T>, String> {
+        let link = Link::new(self, name)?;
+        let linked = LinkedVar::<T> {
+            link,
+            cell: LinkCell(Box::into_raw(Box::new(value.into_storage()))),
+        };
+
+        let status = (self.stubs().Tcl_LinkVar)(
+            self as *const Interpreter,
+            linked.link.name.as_ptr(),
+            linked.cell.0 as _,
+            T::LINK_TYPE | link_flags(mode),
+        );
+        linked.link.check(status)?;
+
+        Ok(linked)
+    }
+
+    /// Links the Tcl variable `name` to a Rust-owned buffer.
+    ///
+    /// This wraps [Tcl_LinkArray](https://www.tcl.tk/man/tcl/TclLib/LinkVar.html).
+    /// The buffer keeps the length of `values` for the lifetime of the link
+    /// and Tcl sees it as a list of that many elements.
+    ///
+    /// Tcl 8.6 has no `Tcl_LinkArray`, so this needs the `tcl90` feature.
+    #[cfg(feature = "tcl90")]
+    pub fn link_array<T: LinkElement>(
+        &self,
+        name: &str,
+        values: Vec<T>,
+        mode: LinkMode,
+    ) -> Result<LinkedArray<T>, String> {
+        if values.is_empty() {
+            return Err("cannot link an empty array".to_string());
+        }
+
+        let link = Link::new(self, name)?;
+        let cells: Box<[T::Storage]> = values.into_iter().map(T::into_storage).collect();
+        let linked = LinkedArray::<T> {
+            link,
+            cells: LinkCells(Box::into_raw(cells)),
+        };
+
+        let status = (self.stubs().Tcl_LinkArray)(
+            self as *const Interpreter,
+            linked.link.name.as_ptr(),
+            linked.cells.0 as *mut c_void,
+            T::LINK_TYPE | link_flags(mode),
+            linked.len(),
+        );
+        linked.link.check(status)?;
+
+        Ok(linked)
+    }
+}
+
+fn link_flags(mode: LinkMode) -> c_int {
+    match mode {
+        LinkMode::ReadWrite => 0,
+        LinkMode::ReadOnly => TCL_LINK_READ_ONLY,
+    }
+}
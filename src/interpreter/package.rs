@@ -0,0 +1,151 @@
+//! Wrappers around Tcl's [package](https://www.tcl.tk/man/tcl/TclLib/PkgRequire.html)
+//! functions.
+//!
+//! Besides loading packages, these allow a package to publish a pointer to
+//! an API table that other extensions fetch when requiring it.
+
+use std::ffi::CStr;
+use std::ffi::CString;
+use std::ffi::c_int;
+use std::ffi::c_void;
+use std::os::raw::c_char;
+
+use crate::Interpreter;
+use crate::TclStatus;
+
+type PkgQuery = extern "C" fn(
+    *const Interpreter,
+    *const c_char,
+    *const c_char,
+    c_int,
+    *mut c_void,
+) -> *const c_char;
+
+impl Interpreter {
+    /// Loads a package (if needed) and returns the version provided.
+    ///
+    /// This is the counterpart of `package require`.  Without `version`, any
+    /// version is accepted.  Otherwise, the package must have a version
+    /// compatible with `version` (the same major version and at least as
+    /// new), or exactly `version` if `exact` is set.
+    pub fn require_package(
+        &self,
+        name: &str,
+        version: Option<&str>,
+        exact: bool,
+    ) -> Result<String, String> {
+        let query = self.stubs().Tcl_PkgRequireEx;
+        self.package_query(query, name, version, exact, std::ptr::null_mut())
+    }
+
+    /// Returns the version of a package if it is already loaded.
+    ///
+    /// This is the counterpart of `package present` and, unlike
+    /// [require_package](Interpreter::require_package), never loads the
+    /// package.  `version` and `exact` are interpreted the same way.
+    pub fn package_present(
+        &self,
+        name: &str,
+        version: Option<&str>,
+        exact: bool,
+    ) -> Result<String, String> {
+        let query = self.stubs().Tcl_PkgPresentEx;
+        self.package_query(query, name, version, exact, std::ptr::null_mut())
+    }
+
+    /// Informs the Tcl interpreter that the given package and version is
+    /// available, publishing `data` for other extensions.
+    ///
+    /// Extensions depending on this package retrieve `data` with
+    /// [require_package_data](Interpreter::require_package_data).  `data` is
+    /// usually a `static` table of function pointers, declared `#[repr(C)]`
+    /// so that its layout does not depend on how each extension was
+    /// compiled.
+    pub fn provide_package_data<T: Sync>(
+        &self,
+        name: &str,
+        version: &str,
+        data: &'static T,
+    ) -> Result<TclStatus, String> {
+        let name = CString::new(name).map_err(|_| "unexpected Nul in package name".to_string())?;
+        let version =
+            CString::new(version).map_err(|_| "unexpected Nul in package version".to_string())?;
+        let status = (self.stubs().Tcl_PkgProvideEx)(
+            self as *const Interpreter,
+            name.as_ptr(),
+            version.as_ptr(),
+            data as *const T as *const c_void,
+        );
+        if status == 0 {
+            Ok(TclStatus::Ok)
+        } else {
+            Err(self.get_obj_result().get_string().to_string())
+        }
+    }
+
+    /// Loads a package (if needed) and fetches the data it published with
+    /// [provide_package_data](Interpreter::provide_package_data).
+    ///
+    /// Returns the version provided along with the data.  An error is
+    /// returned if the package cannot be loaded or published no data.
+    ///
+    /// # Safety
+    ///
+    /// The package must have published a `T` (with the same definition and,
+    /// across extensions, a `#[repr(C)]` layout).  This cannot be checked at
+    /// runtime.
+    pub unsafe fn require_package_data<T: Sync>(
+        &self,
+        name: &str,
+        version: Option<&str>,
+        exact: bool,
+    ) -> Result<(String, &'static T), String> {
+        let query = self.stubs().Tcl_PkgRequireEx;
+        let mut data: *const c_void = std::ptr::null();
+        let version = self.package_query(
+            query,
+            name,
+            version,
+            exact,
+            &mut data as *mut *const c_void as *mut c_void,
+        )?;
+
+        match unsafe { (data as *const T).as_ref() } {
+            Some(data) => Ok((version, data)),
+            None => Err(format!("package \"{}\" provides no data", name)),
+        }
+    }
+
+    fn package_query(
+        &self,
+        query: PkgQuery,
+        name: &str,
+        version: Option<&str>,
+        exact: bool,
+        data: *mut c_void,
+    ) -> Result<String, String> {
+        let name = CString::new(name).map_err(|_| "unexpected Nul in package name".to_string())?;
+        let version = version
+            .map(CString::new)
+            .transpose()
+            .map_err(|_| "unexpected Nul in package version".to_string())?;
+
+        let provided = query(
+            self as *const Interpreter,
+            name.as_ptr(),
+            version
+                .as_ref()
+                .map_or(std::ptr::null(), |version| version.as_ptr()),
+            exact as c_int,
+            data,
+        );
+        if provided.is_null() {
+            return Err(self.get_obj_result().get_string().to_string());
+        }
+
+        Ok(unsafe { CStr::from_ptr(provided) }
+            .to_str()
+            .expect("invalid package version from Tcl")
+            .to_string())
+    }
+}
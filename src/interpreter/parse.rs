@@ -0,0 +1,530 @@
+//! Wrappers around Tcl's [script parser](https://www.tcl.tk/man/tcl/TclLib/ParseCmd.html).
+//!
+//! The parser splits scripts into commands and words exactly as the Tcl
+//! interpreter does, without evaluating anything.  Results are returned as
+//! owned syntax trees whose tokens refer to byte ranges of the parsed
+//! string.
+
+use std::ffi::CString;
+use std::ffi::c_int;
+use std::fmt::Display;
+use std::ops::Range;
+use std::os::raw::c_char;
+
+use super::stubs::Tcl_Parse;
+use crate::Error;
+use crate::Interpreter;
+use crate::TclSize;
+use crate::tcl::from_tcl_size;
+use crate::tcl::try_to_tcl_size;
+
+const TCL_TOKEN_WORD: c_int = 1;
+const TCL_TOKEN_SIMPLE_WORD: c_int = 2;
+const TCL_TOKEN_TEXT: c_int = 4;
+const TCL_TOKEN_BS: c_int = 8;
+const TCL_TOKEN_COMMAND: c_int = 16;
+const TCL_TOKEN_VARIABLE: c_int = 32;
+const TCL_TOKEN_SUB_EXPR: c_int = 64;
+const TCL_TOKEN_OPERATOR: c_int = 128;
+const TCL_TOKEN_EXPAND_WORD: c_int = 256;
+
+const NUM_STATIC_TOKENS: usize = 20;
+
+/// Mirrors `Tcl_Token`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+struct RawToken {
+    kind: c_int,
+    start: *const c_char,
+    size: TclSize,
+    num_components: TclSize,
+}
+
+/// Mirrors Tcl 9's `Tcl_Parse`.
+///
+/// Tcl points `token_ptr` at `static_tokens` for small parses, so a value of
+/// this type must not move while Tcl uses it (it is always boxed).
+#[cfg(feature = "tcl90")]
+#[repr(C)]
+struct RawParse {
+    comment_start: *const c_char,
+    comment_size: TclSize,
+    command_start: *const c_char,
+    command_size: TclSize,
+    num_words: TclSize,
+    token_ptr: *mut RawToken,
+    num_tokens: TclSize,
+    tokens_available: TclSize,
+    error_type: c_int,
+    incomplete: c_int,
+    string: *const c_char,
+    end: *const c_char,
+    interp: *const Interpreter,
+    term: *const c_char,
+    static_tokens: [RawToken; NUM_STATIC_TOKENS],
+}
+
+/// Mirrors Tcl 8.6's `Tcl_Parse`, which places `incomplete` after `term`.
+#[cfg(feature = "tcl86")]
+#[repr(C)]
+struct RawParse {
+    comment_start: *const c_char,
+    comment_size: TclSize,
+    command_start: *const c_char,
+    command_size: TclSize,
+    num_words: TclSize,
+    token_ptr: *mut RawToken,
+    num_tokens: TclSize,
+    tokens_available: TclSize,
+    error_type: c_int,
+    string: *const c_char,
+    end: *const c_char,
+    interp: *const Interpreter,
+    term: *const c_char,
+    incomplete: c_int,
+    static_tokens: [RawToken; NUM_STATIC_TOKENS],
+}
+
+impl RawParse {
+    fn new() -> Box<RawParse> {
+        // All fields are integers or raw pointers, for which zero is valid.
+        Box::new(unsafe { std::mem::zeroed() })
+    }
+
+    fn tokens(&self) -> &[RawToken] {
+        if self.token_ptr.is_null() || self.num_tokens == 0 {
+            &[]
+        } else {
+            unsafe { std::slice::from_raw_parts(self.token_ptr, from_tcl_size(self.num_tokens)) }
+        }
+    }
+}
+
+/// The kind of a [Token].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    /// Literal text.
+    Text,
+    /// A backslash sequence such as `\n`.
+    Backslash,
+    /// A command substitution (`[...]`), including the brackets.
+    Command,
+    /// A variable substitution.  Its first component is the variable name
+    /// and any further components make up the array index.
+    Variable,
+    /// A (sub)expression in an expression parse.  Its first component is
+    /// the operator (if any) and the rest are the operands.
+    SubExpr,
+    /// An operator in an expression parse.
+    Operator,
+}
+
+/// A token of a parsed word or expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    /// What the token represents.
+    pub kind: TokenKind,
+    /// The byte range of the token in the parsed string.
+    pub range: Range<usize>,
+    /// Nested tokens (for variables and expressions).
+    pub components: Vec<Token>,
+}
+
+/// A word of a parsed command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Word {
+    /// The byte range of the word (including braces or quotes).
+    pub range: Range<usize>,
+    /// Whether the word is prefixed by the `{*}` expansion syntax.
+    pub expand: bool,
+    /// Whether the word is a literal without substitutions (e.g., braced).
+    pub simple: bool,
+    /// The tokens making up the word's value.
+    pub tokens: Vec<Token>,
+}
+
+/// A parsed command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Command {
+    /// The byte range of the command, including its terminator.
+    pub range: Range<usize>,
+    /// The byte range of any comments immediately preceding the command.
+    pub comment: Option<Range<usize>>,
+    /// The words of the command.
+    pub words: Vec<Word>,
+}
+
+/// An error encountered while parsing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// Tcl's description of the problem.
+    pub message: String,
+    /// Whether the error is due to the input ending early (e.g., an
+    /// unclosed brace), in which case more input could complete it.
+    pub incomplete: bool,
+    /// The byte offset where the problem was detected.
+    pub position: usize,
+}
+
+impl ParseError {
+    /// Reports input that is longer than Tcl can parse.
+    fn too_large(err: Error) -> ParseError {
+        ParseError {
+            message: err.to_string(),
+            incomplete: false,
+            position: 0,
+        }
+    }
+
+    /// Reports a parse result Tcl is not expected to produce.
+    fn unexpected(message: String, position: usize) -> ParseError {
+        ParseError {
+            message,
+            incomplete: false,
+            position,
+        }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl From<ParseError> for String {
+    fn from(err: ParseError) -> String {
+        err.message
+    }
+}
+
+/// An iterator over the commands of a script.
+///
+/// Created by [parse_script](Interpreter::parse_script).  Iteration stops
+/// after the first error.
+pub struct ScriptParser<'a> {
+    interp: &'a Interpreter,
+    script: &'a str,
+    position: usize,
+    nested: bool,
+}
+
+impl Iterator for ScriptParser<'_> {
+    type Item = Result<Command, ParseError>;
+
+    fn next(&mut self) -> Option<Result<Command, ParseError>> {
+        while self.position < self.script.len() {
+            let len = match try_to_tcl_size(self.script.len() - self.position) {
+                Ok(len) => len,
+                Err(err) => {
+                    self.position = self.script.len();
+                    return Some(Err(ParseError::too_large(err)));
+                }
+            };
+            let mut parse = RawParse::new();
+            let status = (self.interp.stubs().Tcl_ParseCommand)(
+                self.interp as *const Interpreter,
+                self.script[self.position..].as_ptr() as *const c_char,
+                len,
+                self.nested as c_int,
+                (&mut *parse as *mut RawParse).cast(),
+            );
+            if status != 0 {
+                let err = self.interp.parse_error(self.script, &parse);
+                self.interp.free_parse(&mut parse);
+                self.position = self.script.len();
+                return Some(Err(err));
+            }
+
+            let base = self.script.as_ptr();
+            let range = offset(base, parse.command_start)
+                ..offset(base, parse.command_start) + from_tcl_size(parse.command_size);
+            let comment = if parse.comment_size > 0 {
+                let start = offset(base, parse.comment_start);
+                Some(start..start + from_tcl_size(parse.comment_size))
+            } else {
+                None
+            };
+            let words = words(base, parse.tokens());
+            // In a command substitution, `term` points at the closing bracket
+            // once it is reached.
+            let closed = self.nested
+                && !parse.term.is_null()
+                && self.script.as_bytes().get(offset(base, parse.term)) == Some(&b']');
+            self.interp.free_parse(&mut parse);
+            let words = match words {
+                Ok(words) => words,
+                Err(err) => {
+                    self.position = self.script.len();
+                    return Some(Err(err));
+                }
+            };
+
+            if range.end <= self.position || closed {
+                // Only trailing whitespace or comments remained, or the
+                // command substitution was closed.
+                self.position = self.script.len();
+            } else {
+                self.position = range.end;
+            }
+
+            if !words.is_empty() || comment.is_some() {
+                return Some(Ok(Command {
+                    range,
+                    comment,
+                    words,
+                }));
+            }
+        }
+
+        None
+    }
+}
+
+fn offset(base: *const u8, ptr: *const c_char) -> usize {
+    ptr as usize - base as usize
+}
+
+fn tokens(base: *const u8, raw: &[RawToken]) -> Result<Vec<Token>, ParseError> {
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < raw.len() {
+        let token = &raw[i];
+        let children = &raw[i + 1..i + 1 + from_tcl_size(token.num_components)];
+        let kind = match token.kind {
+            TCL_TOKEN_TEXT => TokenKind::Text,
+            TCL_TOKEN_BS => TokenKind::Backslash,
+            TCL_TOKEN_COMMAND => TokenKind::Command,
+            TCL_TOKEN_VARIABLE => TokenKind::Variable,
+            TCL_TOKEN_SUB_EXPR => TokenKind::SubExpr,
+            TCL_TOKEN_OPERATOR => TokenKind::Operator,
+            _ => {
+                return Err(ParseError::unexpected(
+                    format!("unexpected token type {} from Tcl", token.kind),
+                    offset(base, token.start),
+                ));
+            }
+        };
+        let start = offset(base, token.start);
+        tokens.push(Token {
+            kind,
+            range: start..start + from_tcl_size(token.size),
+            components: self::tokens(base, children)?,
+        });
+        i += 1 + from_tcl_size(token.num_components);
+    }
+    Ok(tokens)
+}
+
+fn words(base: *const u8, raw: &[RawToken]) -> Result<Vec<Word>, ParseError> {
+    let mut words = Vec::new();
+    let mut i = 0;
+    while i < raw.len() {
+        let token = &raw[i];
+        debug_assert!(matches!(
+            token.kind,
+            TCL_TOKEN_WORD | TCL_TOKEN_SIMPLE_WORD | TCL_TOKEN_EXPAND_WORD
+        ));
+        let start = offset(base, token.start);
+        words.push(Word {
+            range: start..start + from_tcl_size(token.size),
+            expand: token.kind == TCL_TOKEN_EXPAND_WORD,
+            simple: token.kind == TCL_TOKEN_SIMPLE_WORD,
+            tokens: tokens(
+                base,
+                &raw[i + 1..i + 1 + from_tcl_size(token.num_components)],
+            )?,
+        });
+        i += 1 + from_tcl_size(token.num_components);
+    }
+    Ok(words)
+}
+
+fn first_token(tokens: Vec<Token>) -> Result<Token, ParseError> {
+    tokens
+        .into_iter()
+        .next()
+        .ok_or_else(|| ParseError::unexpected("no tokens from Tcl".to_string(), 0))
+}
+
+type ParseWithTerm = extern "C" fn(
+    *const Interpreter,
+    *const c_char,
+    TclSize,
+    *mut Tcl_Parse,
+    c_int,
+    *mut *const c_char,
+) -> c_int;
+
+impl Interpreter {
+    /// Parses a script into its commands.
+    ///
+    /// This wraps `Tcl_ParseCommand`, applied repeatedly to consume the whole
+    /// script.  Nothing is evaluated, so command substitutions are returned
+    /// as [TokenKind::Command] tokens whose contents can be parsed in turn
+    /// with [parse_command_substitution](Interpreter::parse_command_substitution).
+    /// Parse errors leave a message in the interpreter's result.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rtea::*;
+    ///
+    /// fn command_names(interp: &Interpreter, script: &str) -> Result<Vec<String>, ParseError> {
+    ///     let mut names = Vec::new();
+    ///     for command in interp.parse_script(script) {
+    ///         let command = command?;
+    ///         if let Some(word) = command.words.first() {
+    ///             names.push(script[word.range.clone()].to_string());
+    ///         }
+    ///     }
+    ///     Ok(names)
+    /// }
+    /// ```
+    pub fn parse_script<'a>(&'a self, script: &'a str) -> ScriptParser<'a> {
+        ScriptParser {
+            interp: self,
+            script,
+            position: 0,
+            nested: false,
+        }
+    }
+
+    /// Parses the script inside a command substitution.
+    ///
+    /// `script` should be the text following the opening `[`; parsing stops
+    /// at the matching `]`.  Ranges are relative to `script`.
+    pub fn parse_command_substitution<'a>(&'a self, script: &'a str) -> ScriptParser<'a> {
+        ScriptParser {
+            interp: self,
+            script,
+            position: 0,
+            nested: true,
+        }
+    }
+
+    /// Parses an expression (as given to `expr`).
+    ///
+    /// This wraps `Tcl_ParseExpr`.  The result is a single
+    /// [TokenKind::SubExpr] token describing the whole expression.
+    pub fn parse_expr(&self, expr: &str) -> Result<Token, ParseError> {
+        let len = try_to_tcl_size(expr.len()).map_err(ParseError::too_large)?;
+        let mut parse = RawParse::new();
+        let status = (self.stubs().Tcl_ParseExpr)(
+            self as *const Interpreter,
+            expr.as_ptr() as *const c_char,
+            len,
+            (&mut *parse as *mut RawParse).cast(),
+        );
+        first_token(self.finish_parse(expr, &mut parse, status)?)
+    }
+
+    /// Parses a braced string such as `{a {b} c}`.
+    ///
+    /// This wraps `Tcl_ParseBraces`.  `text` must start with the opening
+    /// brace.  Returns the tokens for the content between the braces and the
+    /// byte offset just past the closing brace.
+    pub fn parse_braces(&self, text: &str) -> Result<(Vec<Token>, usize), ParseError> {
+        let parse_braces = self.stubs().Tcl_ParseBraces;
+        self.parse_with_term(parse_braces, text)
+    }
+
+    /// Parses a quoted string such as `"a $b [c]"`.
+    ///
+    /// This wraps `Tcl_ParseQuotedString`.  `text` must start with the
+    /// opening quote.  Returns the tokens for the content between the quotes
+    /// and the byte offset just past the closing quote.
+    pub fn parse_quoted_string(&self, text: &str) -> Result<(Vec<Token>, usize), ParseError> {
+        let parse_quoted_string = self.stubs().Tcl_ParseQuotedString;
+        self.parse_with_term(parse_quoted_string, text)
+    }
+
+    /// Parses a variable substitution such as `$a(b)`.
+    ///
+    /// This wraps `Tcl_ParseVarName`.  `text` must start with the `$`.
+    /// Returns the [TokenKind::Variable] token, whose range ends where the
+    /// substitution does.  If the `$` is not followed by a variable name,
+    /// the token is a [TokenKind::Text] token for the lone `$`.
+    pub fn parse_var_name(&self, text: &str) -> Result<Token, ParseError> {
+        if !text.starts_with('$') {
+            return Err(ParseError {
+                message: "variable substitution must start with \"$\"".to_string(),
+                incomplete: text.is_empty(),
+                position: 0,
+            });
+        }
+
+        let len = try_to_tcl_size(text.len()).map_err(ParseError::too_large)?;
+        let mut parse = RawParse::new();
+        let status = (self.stubs().Tcl_ParseVarName)(
+            self as *const Interpreter,
+            text.as_ptr() as *const c_char,
+            len,
+            (&mut *parse as *mut RawParse).cast(),
+            0,
+        );
+        first_token(self.finish_parse(text, &mut parse, status)?)
+    }
+
+    /// Checks whether a script is complete.
+    ///
+    /// This wraps `Tcl_CommandComplete` and returns `false` when the script
+    /// ends inside an unclosed brace, quote or bracket, which is how
+    /// interactive shells decide whether to prompt for more input.
+    pub fn command_complete(&self, script: &str) -> Result<bool, String> {
+        let script = CString::new(script).map_err(|_| "unexpected Nul in script".to_string())?;
+        Ok((self.stubs().Tcl_CommandComplete)(script.as_ptr()) != 0)
+    }
+
+    fn parse_with_term(
+        &self,
+        proc: ParseWithTerm,
+        text: &str,
+    ) -> Result<(Vec<Token>, usize), ParseError> {
+        let len = try_to_tcl_size(text.len()).map_err(ParseError::too_large)?;
+        let mut parse = RawParse::new();
+        let mut term: *const c_char = std::ptr::null();
+        let status = proc(
+            self as *const Interpreter,
+            text.as_ptr() as *const c_char,
+            len,
+            (&mut *parse as *mut RawParse).cast(),
+            0,
+            &mut term,
+        );
+        let tokens = self.finish_parse(text, &mut parse, status)?;
+        Ok((tokens, offset(text.as_ptr(), term)))
+    }
+
+    fn finish_parse(
+        &self,
+        text: &str,
+        parse: &mut RawParse,
+        status: c_int,
+    ) -> Result<Vec<Token>, ParseError> {
+        let result = if status == 0 {
+            tokens(text.as_ptr(), parse.tokens())
+        } else {
+            Err(self.parse_error(text, parse))
+        };
+        self.free_parse(parse);
+        result
+    }
+
+    fn parse_error(&self, text: &str, parse: &RawParse) -> ParseError {
+        let position = if parse.term.is_null() {
+            text.len()
+        } else {
+            offset(text.as_ptr(), parse.term).min(text.len())
+        };
+        ParseError {
+            message: self.get_obj_result().get_string().to_string(),
+            incomplete: parse.incomplete != 0,
+            position,
+        }
+    }
+
+    fn free_parse(&self, parse: &mut RawParse) {
+        (self.stubs().Tcl_FreeParse)((parse as *mut RawParse).cast());
+    }
+}
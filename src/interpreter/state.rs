@@ -0,0 +1,98 @@
+//! Typed per-interpreter state built on Tcl's [AssocData](https://www.tcl.tk/man/tcl/TclLib/AssocData.html).
+//!
+//! Each interpreter holds at most one value of any given type.  Values are
+//! dropped (newest first) when the interpreter is deleted, after its
+//! [deletion handlers](Interpreter::call_when_deleted) have run, or when
+//! the extension that stored them during initialization is unloaded.
+
+use std::any::TypeId;
+
+use crate::Interpreter;
+
+impl Interpreter {
+    /// Stores a value of type `T` in the interpreter.
+    ///
+    /// The value lives until the interpreter is deleted and can be retrieved
+    /// with [state](Interpreter::state) from any command.  Each interpreter
+    /// holds at most one value per type, so use a wrapper type to store
+    /// several values of the same type.  If a value of type `T` is already
+    /// stored, it is left in place and `value` is handed back as `Err`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::cell::RefCell;
+    /// use std::collections::HashMap;
+    ///
+    /// use rtea::*;
+    ///
+    /// #[derive(Default)]
+    /// struct Cache(RefCell<HashMap<String, String>>);
+    ///
+    /// fn lookup(interp: &Interpreter, args: Vec<&str>) -> Result<TclStatus, String> {
+    ///     let cache = interp.state::<Cache>().ok_or("cache not initialized")?;
+    ///     let value = cache.0.borrow().get(args[1]).cloned().unwrap_or_default();
+    ///     interp.set_result(&value);
+    ///     Ok(TclStatus::Ok)
+    /// }
+    ///
+    /// fn init(interp: &Interpreter) -> Result<TclStatus, String> {
+    ///     let _ = interp.set_state(Cache::default());
+    ///     interp.create_command("lookup", lookup)?;
+    ///     Ok(TclStatus::Ok)
+    /// }
+    /// ```
+    pub fn set_state<T: 'static>(&self, value: T) -> Result<(), T> {
+        if self.state::<T>().is_some() {
+            return Err(value);
+        }
+
+        self.teardown()
+            .states
+            .borrow_mut()
+            .push((TypeId::of::<T>(), Box::new(value)));
+        // The extension's unload function has returned by the time this runs,
+        // so no references to the value remain.
+        super::unload::record(self, |interp| unsafe {
+            interp.remove_state::<T>();
+        });
+        Ok(())
+    }
+
+    /// Gets the value of type `T` stored with [set_state](Interpreter::set_state).
+    ///
+    /// Use interior mutability (e.g., `RefCell`) in `T` to modify the state.
+    pub fn state<T: 'static>(&self) -> Option<&T> {
+        let states = self.existing_teardown()?.states.borrow();
+        let state = states
+            .iter()
+            .find(|(id, _)| *id == TypeId::of::<T>())?
+            .1
+            .downcast_ref::<T>()? as *const T;
+        // The value is boxed, so it stays in place until it is removed.
+        unsafe { state.as_ref() }
+    }
+
+    /// Removes and drops the value of type `T` stored in the interpreter.
+    ///
+    /// Returns whether a value was stored.
+    ///
+    /// # Safety
+    ///
+    /// No reference obtained from [state](Interpreter::state) for type `T`
+    /// may be in use, as it would be left dangling.
+    pub unsafe fn remove_state<T: 'static>(&self) -> bool {
+        let Some(teardown) = self.existing_teardown() else {
+            return false;
+        };
+        let mut states = teardown.states.borrow_mut();
+        let Some(index) = states.iter().position(|(id, _)| *id == TypeId::of::<T>()) else {
+            return false;
+        };
+        let state = states.remove(index);
+        // Dropping the value may access the interpreter's state.
+        drop(states);
+        drop(state);
+        true
+    }
+}
@@ -0,0 +1,18 @@
+//! The layout of the Tcl stubs tables (`TclStubs` in `tclDecls.h` and the
+//! tables reachable through its `hooks`).
+//!
+//! The declarations are generated by `build.rs` from the `.decls` files of
+//! the Tcl version selected by the `tcl86`/`tcl90` features.
+
+#![allow(dead_code)]
+
+use crate::Interpreter;
+use crate::ObjectType;
+use crate::RawObject;
+// Tcl 8.6 declares no functions taking a `Tcl_Size`.
+#[cfg_attr(feature = "tcl86", allow(unused_imports))]
+use crate::tcl::TclSize;
+
+include!(concat!(env!("OUT_DIR"), "/stubs.rs"));
+
+pub(super) type Stubs = TclStubs;
@@ -0,0 +1,66 @@
+//! Wrapper around Tcl's [substitution](https://www.tcl.tk/man/tcl/TclLib/SubstObj.html)
+//! function.
+
+use std::ffi::c_int;
+use std::ops::BitOr;
+
+use crate::EvalError;
+use crate::Interpreter;
+use crate::Object;
+use crate::RawObject;
+use crate::TclStatus;
+
+/// Flags selecting which substitutions [subst](Interpreter::subst) performs.
+///
+/// Flags may be combined with `|`.  These correspond to the `-nobackslashes`,
+/// `-nocommands` and `-novariables` options of the `subst` command, inverted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SubstFlags(c_int);
+
+impl SubstFlags {
+    /// Substitute `[command]` sequences with the command's result.
+    pub const COMMANDS: SubstFlags = SubstFlags(0o1);
+    /// Substitute `$variable` sequences with the variable's value.
+    pub const VARIABLES: SubstFlags = SubstFlags(0o2);
+    /// Substitute backslash sequences such as `\n`.
+    pub const BACKSLASHES: SubstFlags = SubstFlags(0o4);
+    /// Perform all substitutions, as `subst` does without options.
+    pub const ALL: SubstFlags = SubstFlags(0o7);
+}
+
+impl Default for SubstFlags {
+    fn default() -> SubstFlags {
+        SubstFlags::ALL
+    }
+}
+
+impl BitOr for SubstFlags {
+    type Output = SubstFlags;
+
+    fn bitor(self, rhs: SubstFlags) -> SubstFlags {
+        SubstFlags(self.0 | rhs.0)
+    }
+}
+
+impl Interpreter {
+    /// Performs Tcl substitutions on a template.
+    ///
+    /// This wraps `Tcl_SubstObj` and follows the semantics of the `subst`
+    /// command, restricted to the substitutions selected by `flags`.
+    /// Substitutions are evaluated in the current call frame.  Within a
+    /// command substitution, `break` ends the substitution early and
+    /// `continue` substitutes an empty string.
+    ///
+    /// `Tcl_SubstObj` is built on Tcl's non-recursive engine (NRE), but as a
+    /// plain C call it cannot be suspended, so a coroutine cannot yield from
+    /// within a substitution started here.
+    pub fn subst(&self, template: &Object, flags: SubstFlags) -> Result<Object, EvalError> {
+        let result = (self.stubs().Tcl_SubstObj)(self as *const Interpreter, template.obj, flags.0);
+        if result.is_null() {
+            Err(self.eval_error(TclStatus::Error as c_int))
+        } else {
+            // The new object has no references yet, so `Object` takes the first.
+            Ok(RawObject::wrap(result))
+        }
+    }
+}
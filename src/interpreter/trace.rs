@@ -0,0 +1,691 @@
+//! Wrappers around Tcl's [variable](https://www.tcl.tk/man/tcl/TclLib/TraceVar.html),
+//! [command](https://www.tcl.tk/man/tcl/TclLib/TraceCmd.html) and
+//! [execution](https://www.tcl.tk/man/tcl/TclLib/CrtTrace.html) trace
+//! facilities.
+//!
+//! Traces registered through this module are owned by a Rust guard object.
+//! Dropping the guard removes the trace from the interpreter (if Tcl has not
+//! already done so because the traced entity or the interpreter itself went
+//! away).  Traces registered by an extension's `*_Init` function are also
+//! removed when the extension is unloaded, even if their guards were
+//! detached or leaked.
+
+use std::cell::Cell;
+use std::cell::RefCell;
+use std::ffi::CStr;
+use std::ffi::CString;
+use std::ffi::c_int;
+use std::ffi::c_void;
+use std::ops::BitOr;
+use std::os::raw::c_char;
+use std::rc::Rc;
+
+use super::stubs::Tcl_Command;
+use super::stubs::Tcl_Trace;
+use super::unload;
+use crate::Interpreter;
+use crate::Object;
+use crate::RawObject;
+use crate::TclStatus;
+use crate::tcl::*;
+
+/// Flags for [trace_var](Interpreter::trace_var).
+///
+/// The operations (`READS`, `WRITES`, `UNSETS` and `ARRAY`) select which
+/// accesses invoke the trace and may be combined with `|`.  `GLOBAL_ONLY`
+/// and `NAMESPACE_ONLY` control how the variable name is resolved.  The
+/// same type is passed back to the trace callback describing the operation
+/// in progress, in which case `DESTROYED` and `INTERP_DESTROYED` may also
+/// be set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VarTraceFlags(c_int);
+
+impl VarTraceFlags {
+    /// Resolve the variable name in the global namespace only.
+    pub const GLOBAL_ONLY: VarTraceFlags = VarTraceFlags(0x1);
+    /// Resolve the variable name in the current namespace only.
+    pub const NAMESPACE_ONLY: VarTraceFlags = VarTraceFlags(0x2);
+    /// Invoke the trace when the variable is read.
+    pub const READS: VarTraceFlags = VarTraceFlags(0x10);
+    /// Invoke the trace when the variable is written.
+    pub const WRITES: VarTraceFlags = VarTraceFlags(0x20);
+    /// Invoke the trace when the variable is unset.
+    pub const UNSETS: VarTraceFlags = VarTraceFlags(0x40);
+    /// The trace is being removed because the variable no longer exists.
+    pub const DESTROYED: VarTraceFlags = VarTraceFlags(0x80);
+    /// The variable is being unset because the interpreter is being deleted.
+    pub const INTERP_DESTROYED: VarTraceFlags = VarTraceFlags(0x100);
+    /// Invoke the trace for `array` subcommands on the variable.
+    pub const ARRAY: VarTraceFlags = VarTraceFlags(0x800);
+
+    const RESULT_DYNAMIC: c_int = 0x8000;
+    const OPS_MASK: c_int = 0x10 | 0x20 | 0x40 | 0x800;
+    const LOOKUP_MASK: c_int = 0x1 | 0x2;
+
+    /// Checks whether all flags in `other` are set.
+    pub fn contains(self, other: VarTraceFlags) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl BitOr for VarTraceFlags {
+    type Output = VarTraceFlags;
+
+    fn bitor(self, rhs: VarTraceFlags) -> VarTraceFlags {
+        VarTraceFlags(self.0 | rhs.0)
+    }
+}
+
+type VarTraceProc = dyn Fn(&Interpreter, &str, Option<&str>, VarTraceFlags) -> Result<(), String>;
+
+struct VarTraceState {
+    interp: *const Interpreter,
+    name: CString,
+    element: Option<CString>,
+    flags: c_int,
+    ops: c_int,
+    active: Cell<bool>,
+    proc: Box<VarTraceProc>,
+}
+
+impl VarTraceState {
+    fn element_ptr(&self) -> *const c_char {
+        self.element
+            .as_ref()
+            .map_or(std::ptr::null(), |element| element.as_ptr())
+    }
+
+    /// Removes the trace from the interpreter, unless Tcl already did.
+    fn remove(self: &Rc<Self>) {
+        if !self.active.replace(false) {
+            return;
+        }
+
+        let state = Rc::as_ptr(self);
+        if let Ok(interp) = Interpreter::from_raw(self.interp) {
+            (interp.stubs().Tcl_UntraceVar2)(
+                interp as *const Interpreter,
+                self.name.as_ptr(),
+                self.element_ptr(),
+                self.flags,
+                Some(var_trace_proc),
+                state as *mut c_void,
+            );
+        }
+
+        // Tcl no longer references the state so its reference can be released.
+        unsafe { drop(Rc::from_raw(state)) };
+    }
+}
+
+/// A variable trace registered with [trace_var](Interpreter::trace_var).
+///
+/// The trace stays active for as long as this guard is alive.  Dropping it
+/// removes the trace from the interpreter.  If Tcl already removed the trace
+/// (the variable was unset or the interpreter was deleted), dropping the
+/// guard only releases the Rust closure.
+pub struct VarTrace {
+    state: Rc<VarTraceState>,
+}
+
+impl VarTrace {
+    /// Checks whether the trace is still registered with the interpreter.
+    pub fn is_active(&self) -> bool {
+        self.state.active.get()
+    }
+}
+
+impl Drop for VarTrace {
+    fn drop(&mut self) {
+        self.state.remove();
+    }
+}
+
+extern "C" fn var_trace_proc(
+    state: *mut c_void,
+    i: *const Interpreter,
+    name1: *const c_char,
+    name2: *const c_char,
+    flags: c_int,
+) -> *mut c_char {
+    let state = state as *const VarTraceState;
+    let interp = Interpreter::from_raw(i).expect("Tcl passed bad interpreter");
+    let trace = unsafe { state.as_ref() }.expect("variable trace corrupted!");
+
+    let name1 = unsafe { CStr::from_ptr(name1) }
+        .to_str()
+        .expect("invalid variable name from Tcl");
+    let name2 = unsafe { name2.as_ref() }.map(|name2| {
+        unsafe { CStr::from_ptr(name2) }
+            .to_str()
+            .expect("invalid element name from Tcl")
+    });
+
+    let result = if flags & trace.ops != 0 {
+        (trace.proc)(interp, name1, name2, VarTraceFlags(flags))
+    } else {
+        Ok(())
+    };
+
+    if flags & VarTraceFlags::DESTROYED.0 != 0 {
+        // Tcl has already discarded the trace, so the reference it held is
+        // released here and the guard must not try to remove it again.
+        trace.active.set(false);
+        unsafe { drop(Rc::from_raw(state)) };
+    }
+
+    match result {
+        Ok(()) => std::ptr::null_mut(),
+        // `TCL_TRACE_RESULT_DYNAMIC` hands ownership of the message to Tcl.
+        Err(msg) => tcl_string(&msg).0,
+    }
+}
+
+impl Interpreter {
+    /// Attaches a Rust closure as a trace on a variable.
+    ///
+    /// This wraps [Tcl_TraceVar2](https://www.tcl.tk/man/tcl/TclLib/TraceVar.html)
+    /// and invokes `proc` whenever one of the operations selected by `flags`
+    /// is performed on the variable `name` (or its array element `element`).
+    /// The closure receives the variable and element names as seen by the
+    /// script and the flags describing the operation.  Returning `Err` from
+    /// a read or write trace makes the operation fail with the given message;
+    /// errors from unset traces are ignored by Tcl.
+    ///
+    /// When the interpreter is deleted, Tcl unsets its variables and the
+    /// closure is invoked one last time with `UNSETS`, `DESTROYED` and
+    /// `INTERP_DESTROYED` set.  Afterward the returned [VarTrace] may still be
+    /// dropped safely.
+    pub fn trace_var<F>(
+        &self,
+        name: &str,
+        element: Option<&str>,
+        flags: VarTraceFlags,
+        proc: F,
+    ) -> Result<VarTrace, String>
+    where
+        F: Fn(&Interpreter, &str, Option<&str>, VarTraceFlags) -> Result<(), String> + 'static,
+    {
+        let name = CString::new(name).map_err(|_| "unexpected Nul in variable name".to_string())?;
+        let element = element
+            .map(CString::new)
+            .transpose()
+            .map_err(|_| "unexpected Nul in element name".to_string())?;
+
+        let state = Rc::new(VarTraceState {
+            interp: self as *const Interpreter,
+            name,
+            element,
+            // Unsets are always traced so that Rust learns when Tcl discards
+            // the trace, even if the closure is not interested in them.
+            flags: (flags.0 & (VarTraceFlags::LOOKUP_MASK | VarTraceFlags::OPS_MASK))
+                | VarTraceFlags::UNSETS.0
+                | VarTraceFlags::RESULT_DYNAMIC,
+            ops: flags.0 & VarTraceFlags::OPS_MASK,
+            active: Cell::new(false),
+            proc: Box::new(proc),
+        });
+
+        let client_data = Rc::into_raw(state.clone());
+        let status = (self.stubs().Tcl_TraceVar2)(
+            self as *const Interpreter,
+            state.name.as_ptr(),
+            state.element_ptr(),
+            state.flags,
+            Some(var_trace_proc),
+            client_data as *mut c_void,
+        );
+
+        if status != 0 {
+            unsafe { drop(Rc::from_raw(client_data)) };
+            return Err(self.get_obj_result().get_string().to_string());
+        }
+
+        state.active.set(true);
+        let weak = Rc::downgrade(&state);
+        unload::record(self, move |_| {
+            if let Some(state) = weak.upgrade() {
+                state.remove();
+            }
+        });
+        Ok(VarTrace { state })
+    }
+}
+
+/// Flags for [trace_exec](Interpreter::trace_exec).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ExecTraceFlags(c_int);
+
+impl ExecTraceFlags {
+    /// No flags.  Bytecode compilation of commands is disabled while the
+    /// trace exists so that every command invocation is reported.
+    pub const NONE: ExecTraceFlags = ExecTraceFlags(0);
+    /// Keep compiling commands to bytecode.  Commands that Tcl compiles
+    /// inline (e.g., `set` or `incr` inside procedures) are then not
+    /// reported, but scripts run at full speed.
+    pub const ALLOW_INLINE_COMPILATION: ExecTraceFlags = ExecTraceFlags(0x20000);
+}
+
+impl BitOr for ExecTraceFlags {
+    type Output = ExecTraceFlags;
+
+    fn bitor(self, rhs: ExecTraceFlags) -> ExecTraceFlags {
+        ExecTraceFlags(self.0 | rhs.0)
+    }
+}
+
+/// An opaque handle to a Tcl command (`Tcl_Command`).
+///
+/// Tokens remain valid only while the command exists.  Use
+/// [command_name](Interpreter::command_name) to find out which command a
+/// token refers to; the name changes if the command is renamed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CommandToken(pub(crate) *mut c_void);
+
+/// A command invocation reported to an execution trace.
+pub struct ExecTraceEvent<'a> {
+    /// The nesting level of the invocation (1 for commands run at the top
+    /// of the evaluation).
+    pub level: usize,
+    /// The source of the command, if Tcl has it available.
+    pub command: &'a str,
+    /// The command being invoked.
+    pub token: CommandToken,
+    /// The words of the command, after substitution.
+    pub args: &'a [Object],
+}
+
+type ExecTraceProc = dyn Fn(&Interpreter, &ExecTraceEvent) -> Result<(), String>;
+
+struct ExecTraceState {
+    interp: *const Interpreter,
+    token: Cell<Tcl_Trace>,
+    active: Cell<bool>,
+    proc: Box<ExecTraceProc>,
+}
+
+/// An execution trace registered with [trace_exec](Interpreter::trace_exec).
+///
+/// The trace stays active for as long as this guard is alive.  Dropping it
+/// removes the trace from the interpreter.  If the interpreter was deleted
+/// first, dropping the guard only releases the Rust closure.
+pub struct ExecTrace {
+    state: Rc<ExecTraceState>,
+}
+
+impl ExecTrace {
+    /// Checks whether the trace is still registered with the interpreter.
+    pub fn is_active(&self) -> bool {
+        self.state.active.get()
+    }
+}
+
+impl ExecTraceState {
+    /// Removes the trace from the interpreter, unless Tcl already did.
+    fn remove(&self) {
+        if !self.active.get() {
+            return;
+        }
+
+        // Tcl invokes `exec_trace_delete`, which releases the reference Tcl
+        // holds.
+        if let Ok(interp) = Interpreter::from_raw(self.interp) {
+            (interp.stubs().Tcl_DeleteTrace)(interp as *const Interpreter, self.token.get());
+        }
+    }
+}
+
+impl Drop for ExecTrace {
+    fn drop(&mut self) {
+        self.state.remove();
+    }
+}
+
+extern "C" fn exec_trace_proc(
+    state: *mut c_void,
+    i: *const Interpreter,
+    level: TclSize,
+    command: *const c_char,
+    token: Tcl_Command,
+    objc: TclSize,
+    objv: *const *mut RawObject,
+) -> c_int {
+    let (level, objc) = (from_tcl_size(level), from_tcl_size(objc));
+    let interp = Interpreter::from_raw(i).expect("Tcl passed bad interpreter");
+    let trace =
+        unsafe { (state as *const ExecTraceState).as_ref() }.expect("execution trace corrupted!");
+
+    let command = unsafe { command.as_ref() }.map_or("", |command| {
+        unsafe { CStr::from_ptr(command) }
+            .to_str()
+            .expect("invalid command from Tcl")
+    });
+    let args: Vec<Object> = if objc == 0 {
+        Vec::new()
+    } else {
+        unsafe { std::slice::from_raw_parts(objv, objc) }
+            .iter()
+            .map(|obj| RawObject::wrap(*obj))
+            .collect()
+    };
+
+    let event = ExecTraceEvent {
+        level,
+        command,
+        token: CommandToken(token.cast()),
+        args: &args,
+    };
+    match (trace.proc)(interp, &event) {
+        Ok(()) => TclStatus::Ok as c_int,
+        Err(msg) => {
+            interp.set_result(&msg);
+            TclStatus::Error as c_int
+        }
+    }
+}
+
+extern "C" fn exec_trace_delete(state: *mut c_void) {
+    let state = unsafe { Rc::from_raw(state as *const ExecTraceState) };
+    state.active.set(false);
+}
+
+impl Interpreter {
+    /// Attaches a Rust closure as an execution trace.
+    ///
+    /// This wraps [Tcl_CreateObjTrace2](https://www.tcl.tk/man/tcl/TclLib/CrtTrace.html)
+    /// (`Tcl_CreateObjTrace` with Tcl 8.6) and invokes `proc` before every command executed by the interpreter
+    /// at a nesting level of at most `max_level` (or at any level if `None`).
+    /// Returning `Err` from the closure prevents the command from running
+    /// and raises the given message as an error instead.
+    ///
+    /// Commands evaluated by the closure itself are not traced.  See
+    /// [ExecTraceFlags] for the trade-off between completeness and speed.
+    pub fn trace_exec<F>(
+        &self,
+        max_level: Option<usize>,
+        flags: ExecTraceFlags,
+        proc: F,
+    ) -> ExecTrace
+    where
+        F: Fn(&Interpreter, &ExecTraceEvent) -> Result<(), String> + 'static,
+    {
+        let state = Rc::new(ExecTraceState {
+            interp: self as *const Interpreter,
+            token: Cell::new(std::ptr::null_mut()),
+            active: Cell::new(true),
+            proc: Box::new(proc),
+        });
+
+        #[cfg(feature = "tcl86")]
+        let create_trace = self.stubs().Tcl_CreateObjTrace;
+        #[cfg(feature = "tcl90")]
+        let create_trace = self.stubs().Tcl_CreateObjTrace2;
+        let token = create_trace(
+            self as *const Interpreter,
+            saturating_tcl_size(max_level.unwrap_or(0)),
+            flags.0,
+            Some(exec_trace_proc),
+            Rc::into_raw(state.clone()) as *mut c_void,
+            Some(exec_trace_delete),
+        );
+        state.token.set(token);
+        let weak = Rc::downgrade(&state);
+        unload::record(self, move |_| {
+            if let Some(state) = weak.upgrade() {
+                state.remove();
+            }
+        });
+
+        ExecTrace { state }
+    }
+
+    /// Gets the name of a command (as it would be invoked from the current
+    /// namespace).
+    pub fn command_name(&self, token: CommandToken) -> String {
+        let name = (self.stubs().Tcl_GetCommandName)(self as *const Interpreter, token.0.cast());
+        unsafe { CStr::from_ptr(name) }
+            .to_str()
+            .expect("invalid command name from Tcl")
+            .to_string()
+    }
+
+    /// Gets the fully qualified name of a command.
+    pub fn command_full_name(&self, token: CommandToken) -> String {
+        let name = Object::new();
+        (self.stubs().Tcl_GetCommandFullName)(self as *const Interpreter, token.0.cast(), name.obj);
+        name.get_string().to_string()
+    }
+}
+
+/// Flags for [trace_command](Interpreter::trace_command).
+///
+/// `RENAME` and `DELETE` select the operations that invoke the trace and may
+/// be combined with `|`.  The same type is passed back to the callback,
+/// where `DESTROYED` and `INTERP_DESTROYED` may also be set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommandTraceFlags(c_int);
+
+impl CommandTraceFlags {
+    /// Invoke the trace when the command is renamed.
+    pub const RENAME: CommandTraceFlags = CommandTraceFlags(0x2000);
+    /// Invoke the trace when the command is deleted.
+    pub const DELETE: CommandTraceFlags = CommandTraceFlags(0x4000);
+    /// The trace is being removed because the command no longer exists.
+    pub const DESTROYED: CommandTraceFlags = CommandTraceFlags(0x80);
+    /// The command is being deleted because the interpreter is being deleted.
+    pub const INTERP_DESTROYED: CommandTraceFlags = CommandTraceFlags(0x100);
+
+    const OPS_MASK: c_int = 0x2000 | 0x4000;
+
+    /// Checks whether all flags in `other` are set.
+    pub fn contains(self, other: CommandTraceFlags) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl BitOr for CommandTraceFlags {
+    type Output = CommandTraceFlags;
+
+    fn bitor(self, rhs: CommandTraceFlags) -> CommandTraceFlags {
+        CommandTraceFlags(self.0 | rhs.0)
+    }
+}
+
+type CommandTraceProc = dyn Fn(&Interpreter, &str, Option<&str>, CommandTraceFlags);
+
+struct CommandTraceState {
+    interp: *const Interpreter,
+    name: RefCell<CString>,
+    ops: c_int,
+    active: Cell<bool>,
+    proc: Box<CommandTraceProc>,
+}
+
+/// A command trace registered with [trace_command](Interpreter::trace_command).
+///
+/// The trace stays active for as long as this guard is alive, unless it is
+/// [detached](CommandTrace::detach).  Dropping it removes the trace from the
+/// command.  If the command was deleted first, dropping the guard only
+/// releases the Rust closure.
+pub struct CommandTrace {
+    state: Rc<CommandTraceState>,
+    detached: bool,
+}
+
+impl CommandTrace {
+    /// Checks whether the trace is still registered with the command.
+    pub fn is_active(&self) -> bool {
+        self.state.active.get()
+    }
+
+    /// Gets the current name of the traced command.
+    ///
+    /// This follows renames of the command.
+    pub fn command_name(&self) -> String {
+        self.state.name.borrow().to_string_lossy().into_owned()
+    }
+
+    /// Releases the guard without removing the trace.
+    ///
+    /// The trace (and its closure) then lives until the command is deleted,
+    /// which is usually what is wanted for cleanup hooks.
+    pub fn detach(mut self) {
+        self.detached = true;
+    }
+}
+
+impl CommandTraceState {
+    /// Removes the trace from the command, unless Tcl already did.
+    fn remove(self: &Rc<Self>) {
+        if !self.active.replace(false) {
+            return;
+        }
+
+        let state = Rc::as_ptr(self);
+        if let Ok(interp) = Interpreter::from_raw(self.interp) {
+            (interp.stubs().Tcl_UntraceCommand)(
+                interp as *const Interpreter,
+                self.name.borrow().as_ptr(),
+                CommandTraceFlags::OPS_MASK,
+                Some(command_trace_proc),
+                state as *mut c_void,
+            );
+        }
+
+        // Tcl no longer references the state so its reference can be released.
+        unsafe { drop(Rc::from_raw(state)) };
+    }
+}
+
+impl Drop for CommandTrace {
+    fn drop(&mut self) {
+        if !self.detached {
+            self.state.remove();
+        }
+    }
+}
+
+extern "C" fn command_trace_proc(
+    state: *mut c_void,
+    i: *const Interpreter,
+    old_name: *const c_char,
+    new_name: *const c_char,
+    flags: c_int,
+) {
+    let state = state as *const CommandTraceState;
+    let interp = Interpreter::from_raw(i).expect("Tcl passed bad interpreter");
+    let trace = unsafe { state.as_ref() }.expect("command trace corrupted!");
+
+    let old_name = unsafe { CStr::from_ptr(old_name) }
+        .to_str()
+        .expect("invalid command name from Tcl");
+    let new_name = unsafe { new_name.as_ref() }
+        .map(|new_name| {
+            unsafe { CStr::from_ptr(new_name) }
+                .to_str()
+                .expect("invalid command name from Tcl")
+        })
+        .filter(|new_name| !new_name.is_empty());
+
+    if flags & CommandTraceFlags::RENAME.0 != 0
+        && let Some(new_name) = new_name
+    {
+        *trace.name.borrow_mut() = CString::new(new_name).expect("Tcl command names have no Nul");
+    }
+
+    if flags & trace.ops != 0 {
+        (trace.proc)(interp, old_name, new_name, CommandTraceFlags(flags));
+    }
+
+    if flags & CommandTraceFlags::DELETE.0 != 0 {
+        // Tcl discards the trace along with the command, so the reference it
+        // held is released here and the guard must not try to remove it.
+        trace.active.set(false);
+        unsafe { drop(Rc::from_raw(state)) };
+    }
+}
+
+impl Interpreter {
+    /// Attaches a Rust closure as a trace on a command.
+    ///
+    /// This wraps [Tcl_TraceCommand](https://www.tcl.tk/man/tcl/TclLib/TraceCmd.html)
+    /// and invokes `proc` when the command `name` is renamed or deleted, as
+    /// selected by `flags`.  The closure receives the command's fully
+    /// qualified old name and, for renames, its new name.  Renaming a command
+    /// to `""` deletes it.  Deleting the interpreter deletes its commands, so
+    /// delete traces also run then.
+    ///
+    /// Any command can be traced, including those registered with
+    /// [create_command](Interpreter::create_command) or
+    /// [StatefulCommand::attach_command](crate::StatefulCommand::attach_command),
+    /// whose returned token gives the command's
+    /// [full name](Interpreter::command_full_name).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rtea::*;
+    ///
+    /// fn create_handle(interp: &Interpreter, handle: &str) -> Result<TclStatus, String> {
+    ///     fn cmd(_interp: &Interpreter, _args: Vec<&str>) -> Result<TclStatus, String> {
+    ///         Ok(TclStatus::Ok)
+    ///     }
+    ///
+    ///     let token = interp.create_command(handle, cmd)?;
+    ///     let name = interp.command_full_name(token);
+    ///     interp
+    ///         .trace_command(&name, CommandTraceFlags::DELETE, |_, name, _, _| {
+    ///             println!("releasing resources of {}", name);
+    ///         })?
+    ///         .detach();
+    ///     Ok(TclStatus::Ok)
+    /// }
+    /// ```
+    pub fn trace_command<F>(
+        &self,
+        name: &str,
+        flags: CommandTraceFlags,
+        proc: F,
+    ) -> Result<CommandTrace, String>
+    where
+        F: Fn(&Interpreter, &str, Option<&str>, CommandTraceFlags) + 'static,
+    {
+        let name = CString::new(name).map_err(|_| "unexpected Nul in command name".to_string())?;
+
+        let state = Rc::new(CommandTraceState {
+            interp: self as *const Interpreter,
+            name: RefCell::new(name),
+            ops: flags.0 & CommandTraceFlags::OPS_MASK,
+            active: Cell::new(false),
+            proc: Box::new(proc),
+        });
+
+        // Both operations are always traced so that Rust can follow renames
+        // and learn when Tcl discards the trace.
+        let client_data = Rc::into_raw(state.clone());
+        let status = (self.stubs().Tcl_TraceCommand)(
+            self as *const Interpreter,
+            state.name.borrow().as_ptr(),
+            CommandTraceFlags::OPS_MASK,
+            Some(command_trace_proc),
+            client_data as *mut c_void,
+        );
+
+        if status != 0 {
+            unsafe { drop(Rc::from_raw(client_data)) };
+            return Err(self.get_obj_result().get_string().to_string());
+        }
+
+        state.active.set(true);
+        let weak = Rc::downgrade(&state);
+        unload::record(self, move |_| {
+            if let Some(state) = weak.upgrade() {
+                state.remove();
+            }
+        });
+        Ok(CommandTrace {
+            state,
+            detached: false,
+        })
+    }
+}
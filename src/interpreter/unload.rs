@@ -0,0 +1,214 @@
+//! Cleanup of what an extension registered while it was initialized.
+//!
+//! While the [module_init](rtea_proc::module_init) macro runs an extension's
+//! `*_Init` function, the commands, object types, traces, handlers, linked
+//! variables and state it creates in the interpreter through rtea are
+//! recorded.  The
+//! [module_unload](rtea_proc::module_unload) macro removes them again, so no
+//! pointers into the extension's code are left behind when Tcl unmaps it.
+
+use std::cell::RefCell;
+use std::ffi::CStr;
+use std::ffi::CString;
+use std::ffi::c_void;
+
+use super::stubs::Tcl_Command;
+use crate::CommandToken;
+use crate::CommandTraceFlags;
+use crate::Interpreter;
+use crate::TclStatus;
+use crate::TclUnloadFlag;
+
+type Teardown = Box<dyn FnOnce(&Interpreter)>;
+/// Counts the live objects of an object type.
+type LiveObjects = fn() -> usize;
+
+/// What an extension registered with one interpreter.
+#[derive(Default)]
+struct Registry {
+    /// Removes the registrations, in the order they were made.
+    teardowns: Vec<Teardown>,
+    /// The names of the registered object types and how to count their
+    /// live objects.
+    obj_types: Vec<(&'static str, LiveObjects)>,
+}
+
+/// A registry being recorded, with the interpreter it records for.
+type Recording = (*const Interpreter, Registry);
+
+thread_local! {
+    /// The registries of the `*_Init` functions running on this thread.
+    static RECORDING: RefCell<Vec<Recording>> =
+        const { RefCell::new(Vec::new()) };
+}
+
+/// Builds the AssocData key of the registry for `module`.
+fn registry_key(module: &str) -> Result<CString, String> {
+    CString::new(format!("rtea::module::{}", module))
+        .map_err(|_| "unexpected Nul in module name".to_string())
+}
+
+extern "C" fn free_registry(registry: *mut c_void, _interp: *const Interpreter) {
+    unsafe { drop(Box::from_raw(registry as *mut RefCell<Registry>)) };
+}
+
+/// Runs `f` on the registry recording for `interp`, if there is one.
+fn with_registry(interp: &Interpreter, f: impl FnOnce(&mut Registry)) {
+    RECORDING.with_borrow_mut(|recording| {
+        if let Some((_, registry)) = recording
+            .iter_mut()
+            .rev()
+            .find(|(recorded, _)| std::ptr::eq(*recorded, interp))
+        {
+            f(registry);
+        }
+    });
+}
+
+/// Records how to remove something registered with `interp`.
+///
+/// Nothing is recorded unless an extension is being initialized in `interp`.
+pub(super) fn record(interp: &Interpreter, teardown: impl FnOnce(&Interpreter) + 'static) {
+    with_registry(interp, |registry| {
+        registry.teardowns.push(Box::new(teardown))
+    });
+}
+
+/// Records an object type so that unloading can check for live objects.
+pub(super) fn record_obj_type(interp: &Interpreter, name: &'static str, live: LiveObjects) {
+    with_registry(interp, |registry| registry.obj_types.push((name, live)));
+}
+
+/// Records a command so that it is deleted on unload.
+///
+/// A command trace follows the command through renames and notices if it
+/// is deleted before the extension is unloaded.
+pub(super) fn record_command(interp: &Interpreter, token: Tcl_Command) {
+    let recording = RECORDING.with_borrow(|recording| {
+        recording
+            .iter()
+            .any(|(recorded, _)| std::ptr::eq(*recorded, interp))
+    });
+    if !recording {
+        return;
+    }
+
+    let name = interp.command_full_name(CommandToken(token.cast()));
+    let Ok(trace) = interp.trace_command(&name, CommandTraceFlags::DELETE, |_, _, _, _| {}) else {
+        return;
+    };
+    record(interp, move |interp| {
+        if trace.is_active() {
+            let _ = interp.delete_command(&trace.command_name());
+        }
+    });
+}
+
+impl Interpreter {
+    /// Runs an extension's initialization function, recording what it
+    /// registers.
+    ///
+    /// This is called by the [module_init](rtea_proc::module_init) macro, so
+    /// it rarely needs to be called directly.  The commands (including
+    /// [StatefulCommand](crate::StatefulCommand)s), object types, traces,
+    /// deletion, exit and limit handlers, linked variables and
+    /// [state](Interpreter::set_state) that `init` creates in this
+    /// interpreter through rtea are recorded under the name `module`, for
+    /// [unload_module](Interpreter::unload_module) to remove.  Anything
+    /// created in other interpreters (e.g., children created by `init`) is
+    /// not recorded.
+    pub fn init_module<F>(&self, module: &str, init: F) -> Result<TclStatus, String>
+    where
+        F: FnOnce(&Interpreter) -> Result<TclStatus, String>,
+    {
+        let key = registry_key(module)?;
+
+        RECORDING.with_borrow_mut(|recording| {
+            recording.push((self as *const Interpreter, Registry::default()))
+        });
+        let result = init(self);
+        let (_, mut registry) = RECORDING
+            .with_borrow_mut(|recording| recording.pop())
+            .expect("module registry vanished during init");
+
+        // Initializing the extension again in the same interpreter adds to
+        // what was recorded before.
+        if let Some(existing) = self.module_registry(&key) {
+            let mut existing = existing.borrow_mut();
+            existing.teardowns.append(&mut registry.teardowns);
+            existing.obj_types.append(&mut registry.obj_types);
+        } else {
+            (self.stubs().Tcl_SetAssocData)(
+                self as *const Interpreter,
+                key.as_ptr(),
+                Some(free_registry),
+                Box::into_raw(Box::new(RefCell::new(registry))) as *mut c_void,
+            );
+        }
+
+        result
+    }
+
+    /// Runs an extension's unload function and removes what its
+    /// initialization function registered.
+    ///
+    /// This is called by the [module_unload](rtea_proc::module_unload)
+    /// macro, so it rarely needs to be called directly.  After `unload`
+    /// succeeds, everything recorded by [init_module](Interpreter::init_module)
+    /// for `module` is removed from this interpreter, newest first: commands
+    /// are deleted (under their current names), traces and handlers are
+    /// removed (even if their guards were detached), variables are unlinked
+    /// and state is dropped.  If `unload` fails, nothing is removed.
+    ///
+    /// With [DetachFromProcesss](TclUnloadFlag::DetachFromProcesss), the
+    /// extension's code is about to be unmapped, so the unload is refused
+    /// (before `unload` runs) while any object of a registered object type
+    /// is alive.  Tcl offers no way to unregister an object type, so the
+    /// types stay registered by name after the extension is unloaded.
+    pub fn unload_module<F>(
+        &self,
+        module: &str,
+        flags: TclUnloadFlag,
+        unload: F,
+    ) -> Result<TclStatus, String>
+    where
+        F: FnOnce(&Interpreter, TclUnloadFlag) -> Result<TclStatus, String>,
+    {
+        let key = registry_key(module)?;
+
+        if flags == TclUnloadFlag::DetachFromProcesss
+            && let Some(registry) = self.module_registry(&key)
+        {
+            for (name, live) in &registry.borrow().obj_types {
+                let live = live();
+                if live > 0 {
+                    return Err(format!(
+                        "cannot unload \"{}\": {} objects of type \"{}\" are still in use",
+                        module, live, name
+                    ));
+                }
+            }
+        }
+
+        let status = unload(self, flags)?;
+
+        if let Some(registry) = self.module_registry(&key) {
+            let teardowns = std::mem::take(&mut registry.borrow_mut().teardowns);
+            for teardown in teardowns.into_iter().rev() {
+                teardown(self);
+            }
+            (self.stubs().Tcl_DeleteAssocData)(self as *const Interpreter, key.as_ptr());
+        }
+
+        Ok(status)
+    }
+
+    fn module_registry(&self, key: &CStr) -> Option<&RefCell<Registry>> {
+        let registry = (self.stubs().Tcl_GetAssocData)(
+            self as *const Interpreter,
+            key.as_ptr(),
+            std::ptr::null_mut(),
+        );
+        unsafe { (registry as *const RefCell<Registry>).as_ref() }
+    }
+}
@@ -0,0 +1,308 @@
+//! Wrappers around Tcl's [variable](https://www.tcl.tk/man/tcl/TclLib/SetVar.html)
+//! access functions.
+//!
+//! All accesses go through the same paths as Tcl scripts and therefore
+//! honor any traces (including linked variables) on the variables involved.
+
+use std::collections::HashMap;
+use std::ffi::CString;
+use std::ffi::c_int;
+
+use crate::EvalFlags;
+use crate::Interpreter;
+use crate::Object;
+use crate::RawObject;
+use crate::tcl::TclSize;
+use crate::tcl::from_tcl_size;
+
+const TCL_LEAVE_ERR_MSG: c_int = 0x200;
+
+impl Interpreter {
+    /// Gets the value of a variable.
+    ///
+    /// The variable `name` is resolved in the current call frame (for a
+    /// command implemented in Rust this is the frame of the script invoking
+    /// the command) and may name an array element as `array(element)`.  If
+    /// the variable does not exist, or a trace on it fails, the error message
+    /// is passed back as `Err`.
+    pub fn get_var(&self, name: &Object) -> Result<Object, Object> {
+        let value = (self.stubs().Tcl_ObjGetVar2)(
+            self as *const Interpreter,
+            name.obj,
+            std::ptr::null_mut(),
+            TCL_LEAVE_ERR_MSG,
+        );
+        self.var_result(value)
+    }
+
+    /// Sets the value of a variable.
+    ///
+    /// The variable is resolved the same way as in [get_var](Interpreter::get_var)
+    /// and created if needed.  On success the new value of the variable is
+    /// returned, which may differ from `value` if a trace modified it.
+    pub fn set_var(&self, name: &Object, value: &Object) -> Result<Object, Object> {
+        let value = (self.stubs().Tcl_ObjSetVar2)(
+            self as *const Interpreter,
+            name.obj,
+            std::ptr::null_mut(),
+            value.obj,
+            TCL_LEAVE_ERR_MSG,
+        );
+        self.var_result(value)
+    }
+
+    /// Unsets a variable.
+    ///
+    /// The variable is resolved the same way as in [get_var](Interpreter::get_var).
+    /// Unsetting a variable that does not exist is an error.
+    pub fn unset_var(&self, name: &Object) -> Result<(), Object> {
+        let name = CString::new(name.get_string())
+            .map_err(|_| Object::new_string("unexpected Nul in variable name"))?;
+        let status = (self.stubs().Tcl_UnsetVar2)(
+            self as *const Interpreter,
+            name.as_ptr(),
+            std::ptr::null(),
+            TCL_LEAVE_ERR_MSG,
+        );
+        if status == 0 {
+            Ok(())
+        } else {
+            Err(self.get_obj_result())
+        }
+    }
+
+    /// Gets a by-reference handle to a variable.
+    ///
+    /// This is meant for commands which, like `incr` or `lappend`, take the
+    /// name of a variable in their caller as an argument.  The name is not
+    /// bound to a frame: it is resolved in whatever call frame is current
+    /// each time the reference is used.  See [VarRef].
+    pub fn var_ref(&self, name: &Object) -> VarRef<'_> {
+        VarRef {
+            interp: self,
+            name: RawObject::wrap(name.obj),
+        }
+    }
+
+    /// Gets the names of all elements of an array.
+    ///
+    /// The names are enumerated with Tcl's `array names`, invoked directly
+    /// rather than through a script.  An error is returned if `name` is not an
+    /// array.
+    pub fn array_names(&self, name: &Object) -> Result<Vec<String>, Object> {
+        self.array_check(name)?;
+        let names = self.invoke(&["::array", "names"], name)?;
+        Ok(self
+            .list_elements(&names)?
+            .iter()
+            .map(|name| name.get_string().to_string())
+            .collect())
+    }
+
+    /// Iterates over the elements of an array as `(element, value)` pairs.
+    ///
+    /// The contents are snapshotted with Tcl's `array get`, invoked directly
+    /// rather than through a script, so the values are passed as the objects stored
+    /// in the array without being converted to strings.  Changes made to the
+    /// array after this call are not reflected by the iterator.
+    pub fn array_iter(&self, name: &Object) -> Result<ArrayIter, Object> {
+        self.array_check(name)?;
+        let pairs = self.invoke(&["::array", "get"], name)?;
+        Ok(ArrayIter {
+            elements: self.list_elements(&pairs)?.into_iter(),
+        })
+    }
+
+    /// Gets a snapshot of an array as a `HashMap`.
+    ///
+    /// See [array_iter](Interpreter::array_iter).
+    pub fn array_get(&self, name: &Object) -> Result<HashMap<String, Object>, Object> {
+        Ok(self.array_iter(name)?.collect())
+    }
+
+    /// Sets many elements of an array at once.
+    ///
+    /// Each element is written with `Tcl_ObjSetVar2`, so the array is created
+    /// if needed and traces fire as they would for `array set`.  Elements not
+    /// mentioned in `elements` are left untouched.  The first failure stops
+    /// the update and is returned as `Err`.
+    pub fn array_set<K, I>(&self, name: &Object, elements: I) -> Result<(), Object>
+    where
+        K: AsRef<str>,
+        I: IntoIterator<Item = (K, Object)>,
+    {
+        for (key, value) in elements {
+            let key = Object::new_string(key.as_ref());
+            let value = (self.stubs().Tcl_ObjSetVar2)(
+                self as *const Interpreter,
+                name.obj,
+                key.obj,
+                value.obj,
+                TCL_LEAVE_ERR_MSG,
+            );
+            self.var_result(value)?;
+        }
+
+        Ok(())
+    }
+
+    /// Splits a Tcl list into its elements.
+    pub(crate) fn list_elements(&self, list: &Object) -> Result<Vec<Object>, Object> {
+        let mut objc: TclSize = 0;
+        let mut objv: *mut *mut RawObject = std::ptr::null_mut();
+        let status = (self.stubs().Tcl_ListObjGetElements)(
+            self as *const Interpreter,
+            list.obj,
+            &mut objc,
+            &mut objv,
+        );
+        if status != 0 {
+            return Err(self.get_obj_result());
+        }
+        if objc == 0 {
+            return Ok(Vec::new());
+        }
+
+        let raw_elements = unsafe { std::slice::from_raw_parts(objv, from_tcl_size(objc)) };
+        Ok(raw_elements
+            .iter()
+            .map(|obj| RawObject::wrap(*obj))
+            .collect())
+    }
+
+    fn array_check(&self, name: &Object) -> Result<(), Object> {
+        let exists = self.invoke(&["::array", "exists"], name)?;
+        if exists.get_string() == "1" {
+            Ok(())
+        } else {
+            Err(Object::new_string(&format!(
+                "\"{}\" isn't an array",
+                name.get_string()
+            )))
+        }
+    }
+
+    /// Invokes the command `words` with `arg` appended, without parsing a
+    /// script.
+    ///
+    /// The command is looked up in the global namespace, so a command of the
+    /// same name in the current namespace does not take its place, but runs
+    /// in the current call frame.  The interpreter's result and error
+    /// information are left as they were.
+    fn invoke(&self, words: &[&str], arg: &Object) -> Result<Object, Object> {
+        let mut objv: Vec<Object> = words.iter().map(|word| Object::new_string(word)).collect();
+        objv.push(RawObject::wrap(arg.obj));
+
+        let state = (self.stubs().Tcl_SaveInterpState)(self as *const Interpreter, 0);
+        let result = self.eval_objv(&objv, EvalFlags::INVOKE).map_err(Object::from);
+        (self.stubs().Tcl_RestoreInterpState)(self as *const Interpreter, state);
+        result
+    }
+
+    fn var_result(&self, value: *mut RawObject) -> Result<Object, Object> {
+        if value.is_null() {
+            Err(self.get_obj_result())
+        } else {
+            Ok(RawObject::wrap(value))
+        }
+    }
+}
+
+/// An iterator over a snapshot of a Tcl array.
+///
+/// Created by [array_iter](Interpreter::array_iter).
+pub struct ArrayIter {
+    elements: std::vec::IntoIter<Object>,
+}
+
+impl Iterator for ArrayIter {
+    type Item = (String, Object);
+
+    fn next(&mut self) -> Option<(String, Object)> {
+        let key = self.elements.next()?;
+        let value = self.elements.next()?;
+        Some((key.get_string().to_string(), value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let pairs = self.elements.len() / 2;
+        (pairs, Some(pairs))
+    }
+}
+
+/// A reference to a variable in the frame of a command's caller.
+///
+/// Commands implemented in Rust do not push a call frame of their own, so
+/// variable names passed as arguments resolve in the caller's frame while
+/// the command runs.  A `VarRef` wraps such a name (typically one of the
+/// arguments given to a command created with
+/// [create_obj_command](Interpreter::create_obj_command)) and reads and
+/// writes it through Tcl, so traces on the variable are honored.
+///
+/// # Frames
+///
+/// There is no level parameter as with `upvar`: every access resolves the
+/// name in the call frame that is current at that moment, just like
+/// [get_var](Interpreter::get_var).  While the command runs, that is the
+/// caller's frame, which is what `incr`-like commands need.  Used at any
+/// other time (e.g., after the command returned, or from a callback while a
+/// procedure called by the command is running), the same name may refer to
+/// a different variable or to none.
+/// Commands meant to be wrapped by procedures should have the wrapper pass
+/// a fully qualified name, or use `upvar` in the wrapper.
+///
+/// # Example
+///
+/// ```rust
+/// use rtea::*;
+///
+/// // Usage: parse data resultVar
+/// fn parse(interp: &Interpreter, args: Vec<Object>) -> Result<TclStatus, Object> {
+///     if args.len() != 3 {
+///         return Err(Object::new_string("usage: parse data resultVar"));
+///     }
+///     let result = interp.var_ref(&args[2]);
+///     result.set(&Object::new_string(args[1].get_string().trim()))?;
+///     interp.set_result("1");
+///     Ok(TclStatus::Ok)
+/// }
+/// ```
+pub struct VarRef<'a> {
+    interp: &'a Interpreter,
+    name: Object,
+}
+
+impl VarRef<'_> {
+    /// Gets the name of the referenced variable.
+    pub fn name(&self) -> &str {
+        self.name.get_string()
+    }
+
+    /// Gets the value of the referenced variable.
+    pub fn get(&self) -> Result<Object, Object> {
+        self.interp.get_var(&self.name)
+    }
+
+    /// Sets the value of the referenced variable, creating it if needed.
+    pub fn set(&self, value: &Object) -> Result<Object, Object> {
+        self.interp.set_var(&self.name, value)
+    }
+
+    /// Unsets the referenced variable.
+    pub fn unset(&self) -> Result<(), Object> {
+        self.interp.unset_var(&self.name)
+    }
+
+    /// Checks whether the referenced variable currently exists.
+    ///
+    /// Read traces on the variable are invoked by this check.
+    pub fn exists(&self) -> bool {
+        let value = (self.interp.stubs().Tcl_ObjGetVar2)(
+            self.interp as *const Interpreter,
+            self.name.obj,
+            std::ptr::null_mut(),
+            0,
+        );
+        !value.is_null()
+    }
+}
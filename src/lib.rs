@@ -28,7 +28,8 @@
 //!
 //! #[module_init(Example, "1.0.0")]
 //! fn init(interp: &Interpreter) -> Result<TclStatus, String> {
-//!     interp.create_command("example", example)
+//!     interp.create_command("example", example)?;
+//!     Ok(TclStatus::Ok)
 //! }
 //!
 //! fn example(interp: &Interpreter, args: Vec<&str>) -> Result<TclStatus, String> {
@@ -50,17 +51,56 @@
 //! The `module_init` macro already handles registering the "example"
 //! package.
 //!
+//! # Embedding
+//!
+//! With the `embed` feature enabled, rtea links against the Tcl library and
+//! provides `OwnedInterpreter` so that Rust applications can create their
+//! own interpreters and use Tcl as a scripting language.
+//!
+//! # Testing
+//!
+//! The `testing` feature (which implies `embed`) adds the `testing` module,
+//! which creates interpreters for tests, runs extensions' initialization
+//! functions in them and provides assertions on the outcome of scripts.
+//! It also runs an extension's tcltest suites, reporting each case as a
+//! test in `cargo test`.
+//!
+//! # Tcl versions
+//!
+//! rtea targets Tcl 9.0 by default.  Extensions for Tcl 8.6 disable the
+//! default features and enable `tcl86` instead:
+//!
+//! ```toml
+//! rtea = { version = "0.3", default-features = false, features = ["tcl86"] }
+//! ```
+//!
+//! The two versions lay out the stubs table differently and use different
+//! types for sizes (see [TclSize]), so an extension only works with the
+//! version it was built for.  Loading it into the other one fails with
+//! [Error::InvalidStubs].  Methods wrapping functions that only one version
+//! provides (e.g., `link_array`, which needs Tcl 9.0) are only available
+//! with that version's feature.
+//!
 //! # Note
 //!
 //! This code assumes that it extends Tcl and treats any violations of Tcl's
 //! API (unexpected null-pointers, non-UTF8 strings, etc.) as irrecovable
 //! errors that should panic.
 
+#[cfg(all(feature = "tcl86", feature = "tcl90"))]
+compile_error!("the `tcl86` and `tcl90` features are mutually exclusive");
+#[cfg(not(any(feature = "tcl86", feature = "tcl90")))]
+compile_error!("either the `tcl86` or the `tcl90` feature must be enabled");
+
 mod interpreter;
 mod object;
+mod script;
 mod tcl;
+#[cfg(feature = "testing")]
+pub mod testing;
 
 pub use interpreter::*;
 pub use object::*;
 pub use rtea_proc::*;
+pub use script::*;
 pub use tcl::*;
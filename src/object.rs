@@ -4,6 +4,7 @@ use std::ffi::c_void;
 use std::fmt::Display;
 use std::os::raw::c_char;
 
+use crate::Error;
 use crate::Interpreter;
 use crate::TclStatus;
 use crate::tcl::*;
@@ -14,21 +15,22 @@ use crate::tcl::*;
 #[repr(C)]
 #[derive(Debug)]
 pub struct RawObject {
-    pub(crate) ref_count: usize,
+    pub(crate) ref_count: TclSize,
     pub bytes: *mut c_char,
-    pub length: usize,
+    pub length: TclSize,
     pub obj_type: *const ObjectType,
     pub ptr1: *mut c_void,
     pub ptr2: *mut c_void,
 }
 
 impl RawObject {
+    /// Takes a reference to a raw Tcl object.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no interpreter has been initialized yet.
     pub fn wrap(obj: *mut RawObject) -> Object {
-        unsafe { 
-            INCR_REF_COUNT
-            .expect("module must have been initialized")
-            (obj)
-        };
+        global_functions().incr_ref_count(obj);
         Object { obj: obj }
     }
 }
@@ -39,37 +41,66 @@ pub struct Object {
 }
 
 impl Object {
+    /// Creates an empty object.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no interpreter has been initialized yet (see
+    /// [try_new](Object::try_new)).
     pub fn new() -> Object {
-        unsafe { RawObject::wrap(NEW_OBJ.expect("module must have been initialized")()) }
+        Object::try_new().expect("rtea used before an interpreter was initialized")
     }
 
+    /// Creates an empty object, failing with [Error::NotInitialized] if no
+    /// interpreter has been initialized yet.
+    pub fn try_new() -> Result<Object, Error> {
+        let functions = try_global_functions()?;
+        Ok(RawObject::wrap(functions.new_obj()))
+    }
+
+    /// Creates an object holding a string.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `s` contains a Nul, if it is longer than Tcl supports or if
+    /// no interpreter has been initialized yet (see
+    /// [try_new_string](Object::try_new_string)).
     pub fn new_string(s: &str) -> Object {
+        Object::try_new_string(s).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Creates an object holding a string, failing with
+    /// [Error::NotInitialized] if no interpreter has been initialized yet
+    /// and with [Error::TooLarge] if `s` is longer than Tcl supports.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `s` contains a Nul.
+    pub fn try_new_string(s: &str) -> Result<Object, Error> {
+        let functions = try_global_functions()?;
         let cstr = CString::new(s).expect("Unexpected NulError!");
-        unsafe {
-            RawObject::wrap(NEW_STRING_OBJ.expect("module must have been initialized")(
-                cstr.as_ptr() as *const i8,
-                cstr.as_bytes().len() as usize,
-            ))
-        }
+        Ok(RawObject::wrap(functions.new_string_obj(
+            cstr.as_ptr() as *const i8,
+            cstr.as_bytes().len(),
+        )?))
     }
 
+    /// Replaces the string held by the object.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `s` contains a Nul or is longer than Tcl supports.
     pub fn set_string(&self, s: &str) {
         let cstr = CString::new(s).expect("unexpected NulError!");
-        unsafe {
-            SET_STRING_OBJ.expect("module must have been initialized")(
-                self.obj,
-                cstr.as_ptr() as *const i8,
-                cstr.as_bytes().len() as usize,
-            );
-        }
+        global_functions()
+            .set_string_obj(self.obj, cstr.as_ptr() as *const i8, cstr.as_bytes().len())
+            .unwrap_or_else(|err| panic!("{}", err));
     }
 
     /// Gets the string associated with the Tcl object.
     pub fn get_string(&self) -> &str {
         unsafe {
-            CStr::from_ptr(GET_STRING.expect("module must have been initialized")(
-                self.obj,
-            ))
+            CStr::from_ptr(global_functions().get_string(self.obj))
             .to_str()
             .expect("TCL guarantees strings are valid UTF-8")
         }
@@ -93,7 +124,7 @@ impl Object {
 
 impl Drop for Object {
     fn drop(&mut self) {
-        unsafe { DECR_REF_COUNT.expect("module must have been initialized")(self.obj) }
+        global_functions().decr_ref_count(self.obj)
     }
 }
 
@@ -140,6 +171,16 @@ pub trait TclObjectType: Clone + Display {
 
     fn tcl_type() -> &'static ObjectType;
 
+    /// Counts the Tcl objects whose internal representation is of this type.
+    ///
+    /// An extension cannot be unloaded from the process while this is not
+    /// zero, as Tcl would later call into the unmapped code to free them.
+    /// The derive macro keeps an exact count.  The default returns 0, which
+    /// means the count is unknown and does not prevent unloading.
+    fn live_objects() -> usize {
+        0
+    }
+
     fn as_string(&self) -> String {
         format!("{}", self)
     }
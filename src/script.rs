@@ -0,0 +1,215 @@
+//! Safe construction of Tcl commands from Rust values.
+//!
+//! Building scripts with `format!` breaks (or worse, runs unintended code)
+//! as soon as a value contains whitespace, braces, brackets or `$`.  A
+//! [ScriptBuilder] instead keeps each word as a separate [Object], so the
+//! command can be run with [eval_objv](crate::Interpreter::eval_objv)
+//! without any quoting, or rendered as a string with every word quoted as a
+//! proper list element.
+
+use std::fmt::Display;
+use std::os::raw::c_char;
+
+use crate::Error;
+use crate::Object;
+use crate::RawObject;
+use crate::tcl::*;
+
+const TCL_DONT_QUOTE_HASH: i32 = 8;
+
+/// A value usable as a word of a [ScriptBuilder] command.
+///
+/// Objects are used as-is (keeping their internal representation); other
+/// values are converted to their string representation, which panics if no
+/// interpreter has been initialized yet.
+pub trait ScriptArg {
+    /// Converts the value into a command word.
+    fn into_word(self) -> Object;
+}
+
+impl ScriptArg for Object {
+    fn into_word(self) -> Object {
+        self
+    }
+}
+
+impl ScriptArg for &Object {
+    fn into_word(self) -> Object {
+        RawObject::wrap(self.obj)
+    }
+}
+
+impl ScriptArg for &str {
+    fn into_word(self) -> Object {
+        Object::new_string(self)
+    }
+}
+
+impl ScriptArg for String {
+    fn into_word(self) -> Object {
+        Object::new_string(&self)
+    }
+}
+
+impl ScriptArg for &String {
+    fn into_word(self) -> Object {
+        Object::new_string(self)
+    }
+}
+
+macro_rules! display_script_arg {
+    ($($t:ty),*) => {
+        $(
+            impl ScriptArg for $t {
+                fn into_word(self) -> Object {
+                    Object::new_string(&self.to_string())
+                }
+            }
+        )*
+    };
+}
+
+display_script_arg!(
+    bool, char, i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64
+);
+
+/// Builds a Tcl command one word at a time.
+///
+/// Usually created with the [tcl_script](crate::tcl_script) macro.
+///
+/// # Example
+///
+/// ```rust
+/// use rtea::*;
+///
+/// fn notify(interp: &Interpreter, callback: &str, path: &str) -> Result<Object, EvalError> {
+///     // `path` may contain spaces or brackets; it is still passed as a
+///     // single argument and never evaluated.
+///     let command = tcl_script!(callback, "changed", path);
+///     interp.eval_objv(command.words(), EvalFlags::NONE)
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct ScriptBuilder {
+    words: Vec<Object>,
+}
+
+impl ScriptBuilder {
+    /// Creates a builder without any words.
+    pub fn new() -> ScriptBuilder {
+        ScriptBuilder { words: Vec::new() }
+    }
+
+    /// Appends a word to the command.
+    pub fn arg<T: ScriptArg>(mut self, value: T) -> ScriptBuilder {
+        self.words.push(value.into_word());
+        self
+    }
+
+    /// Appends a word to the command in place.
+    pub fn push<T: ScriptArg>(&mut self, value: T) {
+        self.words.push(value.into_word());
+    }
+
+    /// Gets the words of the command, as expected by
+    /// [eval_objv](crate::Interpreter::eval_objv).
+    pub fn words(&self) -> &[Object] {
+        &self.words
+    }
+
+    /// Converts the builder into its words.
+    pub fn into_words(self) -> Vec<Object> {
+        self.words
+    }
+
+    /// Converts the command into a Tcl list object.
+    ///
+    /// Evaluating a list with [eval_obj](crate::Interpreter::eval_obj) runs
+    /// it as a single command without reparsing it, which makes this the
+    /// form to store for callbacks (e.g., with `after` or `trace`).
+    ///
+    /// # Panics
+    ///
+    /// Panics if no interpreter has been initialized yet or if the command
+    /// has more words than Tcl supports.
+    pub fn into_object(self) -> Object {
+        let objv: Vec<*mut RawObject> = self.words.iter().map(|word| word.obj).collect();
+        RawObject::wrap(
+            global_functions()
+                .new_list_obj(&objv)
+                .unwrap_or_else(|err| panic!("{}", err)),
+        )
+    }
+}
+
+/// Renders the command as a string with every word quoted.
+impl Display for ScriptBuilder {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for (i, word) in self.words.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            // Words come from Tcl objects, so their length fits.
+            let word = quote_word(word.get_string(), i == 0).map_err(|_| std::fmt::Error)?;
+            write!(f, "{}", word)?;
+        }
+        Ok(())
+    }
+}
+
+/// Quotes a string so that Tcl parses it as a single list element (and
+/// therefore as a single word of a command).
+///
+/// This wraps `Tcl_ScanCountedElement` and `Tcl_ConvertCountedElement`.
+///
+/// # Panics
+///
+/// Panics if no interpreter has been initialized yet or if `value` is longer
+/// than Tcl supports (see [try_quote_element]).
+pub fn quote_element(value: &str) -> String {
+    try_quote_element(value).unwrap_or_else(|err| panic!("{}", err))
+}
+
+/// Quotes a string like [quote_element], failing with
+/// [Error::NotInitialized] if no interpreter has been initialized yet and
+/// with [Error::TooLarge] if `value` is longer than Tcl supports.
+pub fn try_quote_element(value: &str) -> Result<String, Error> {
+    try_global_functions()?;
+    quote_word(value, false)
+}
+
+fn quote_word(value: &str, first: bool) -> Result<String, Error> {
+    let src = value.as_ptr() as *const c_char;
+    let mut flags: i32 = 0;
+    let size = global_functions().scan_counted_element(src, value.len(), &mut flags)?;
+
+    // A leading `#` only needs quoting where it would start a comment.
+    if !first {
+        flags |= TCL_DONT_QUOTE_HASH;
+    }
+
+    let mut buf = vec![0u8; size + 1];
+    let len = global_functions().convert_counted_element(src, value.len(), buf.as_mut_ptr(), flags);
+    buf.truncate(len);
+    Ok(String::from_utf8(buf).expect("Tcl quoting preserves UTF-8"))
+}
+
+/// Builds a [ScriptBuilder] from a list of values.
+///
+/// Each argument becomes exactly one word of the command, regardless of its
+/// content.  Any type implementing [ScriptArg] may be used.
+///
+/// ```rust
+/// use rtea::*;
+///
+/// fn schedule(interp: &Interpreter, delay: u32, message: &str) -> Result<Object, EvalError> {
+///     let callback = tcl_script!("puts", message).into_object();
+///     interp.eval_objv(tcl_script!("after", delay, callback).words(), EvalFlags::NONE)
+/// }
+/// ```
+#[macro_export]
+macro_rules! tcl_script {
+    ($($word:expr),* $(,)?) => {
+        $crate::ScriptBuilder::new()$(.arg($word))*
+    };
+}
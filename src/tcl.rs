@@ -4,49 +4,284 @@
 //! pointer is either not known (objects & object types) or immediately
 //! available (methods on objects).
 
+use std::ffi::c_int;
+#[cfg(feature = "tcl86")]
+use std::ffi::c_uint;
 use std::ffi::c_void;
 use std::os::raw::c_char;
+use std::sync::OnceLock;
 
-use crate::Interpreter;
-use crate::ObjectType;
+use crate::Error;
 use crate::RawObject;
 
-pub(crate) static mut ALLOC: Option<extern "C" fn(usize) -> *mut c_void> = None;
-pub(crate) static mut REALLOC: Option<extern "C" fn(*mut c_void, usize) -> *mut c_void> = None;
-pub(crate) static mut FREE: Option<extern "C" fn(*mut c_void)> = None;
-
-pub(crate) static mut NEW_OBJ: Option<extern "C" fn() -> *mut RawObject> = None;
-pub(crate) static mut DUPLICATE_OBJ: Option<extern "C" fn(*mut RawObject) -> *mut RawObject> = None;
-pub(crate) static mut INCR_REF_COUNT: Option<extern "C" fn(*mut RawObject)> = None;
-pub(crate) static mut DECR_REF_COUNT: Option<extern "C" fn(*mut RawObject)> = None;
-pub(crate) static mut IS_SHARED: Option<extern "C" fn(*mut RawObject) -> i32> = None;
-pub(crate) static mut INVALIDATE_STRING_REP: Option<extern "C" fn(*mut RawObject)> = None;
-pub(crate) static mut GET_STRING: Option<extern "C" fn(*mut RawObject) -> *mut c_char> = None;
-
-pub(crate) static mut GET_OBJ_TYPE: Option<extern "C" fn(*const c_char) -> *const ObjectType> =
-    None;
-pub(crate) static mut CONVERT_TO_TYPE: Option<
-    extern "C" fn(*const Interpreter, *mut RawObject, *const ObjectType) -> i32,
-> = None;
-
-pub(crate) static mut NEW_STRING_OBJ: Option<
-    extern "C" fn(*const c_char, usize) -> *mut RawObject,
-> = None;
-
-pub(crate) static mut SET_STRING_OBJ: Option<extern "C" fn(*mut RawObject, *const c_char, usize)> =
-    None;
-
-pub fn tcl_string(rust_str: &str) -> (*mut c_char, usize) {
-    let tcl_alloc_len = rust_str.len() + 1;
+/// The type Tcl uses for sizes, lengths and counts (`Tcl_Size`).
+///
+/// This is `usize` with Tcl 9.0 and `c_int` with Tcl 8.6, which limits
+/// strings and lists to 2 GiB.
+#[cfg(feature = "tcl90")]
+pub type TclSize = usize;
+/// The type Tcl uses for sizes, lengths and counts (`Tcl_Size`).
+///
+/// This is `usize` with Tcl 9.0 and `c_int` with Tcl 8.6, which limits
+/// strings and lists to 2 GiB.
+#[cfg(feature = "tcl86")]
+pub type TclSize = c_int;
+
+/// Converts a Rust length into a [TclSize], failing with
+/// [Error::TooLarge] if Tcl cannot represent it.
+///
+/// Only Tcl 8.6 has such a limit.  Lengths that come from users (scripts,
+/// strings, lists) must go through this rather than [to_tcl_size].
+#[cfg(feature = "tcl90")]
+pub(crate) fn try_to_tcl_size(len: usize) -> Result<TclSize, Error> {
+    Ok(len)
+}
+
+#[cfg(feature = "tcl86")]
+pub(crate) fn try_to_tcl_size(len: usize) -> Result<TclSize, Error> {
+    TclSize::try_from(len).map_err(|_| Error::TooLarge(len))
+}
+
+/// Converts a Rust length that is already bounded by Tcl (e.g., the length
+/// of a string Tcl handed out) into a [TclSize].
+///
+/// # Panics
+///
+/// With Tcl 8.6, this panics if the length exceeds what Tcl can represent,
+/// which cannot happen for lengths bounded by Tcl.
+pub(crate) fn to_tcl_size(len: usize) -> TclSize {
+    try_to_tcl_size(len).expect("size exceeds Tcl 8.6 limits")
+}
+
+/// Converts a Rust length into a [TclSize], saturating at the largest size
+/// Tcl can represent.
+pub(crate) fn saturating_tcl_size(len: usize) -> TclSize {
+    try_to_tcl_size(len).unwrap_or(TclSize::MAX)
+}
+
+/// Converts a [TclSize] from Tcl into a Rust length.
+#[cfg(feature = "tcl90")]
+pub(crate) fn from_tcl_size(size: TclSize) -> usize {
+    size
+}
+
+/// Converts a [TclSize] from Tcl into a Rust length.
+///
+/// Tcl never reports negative sizes, but one would be treated as 0 rather
+/// than panicking (possibly in a callback from Tcl).
+#[cfg(feature = "tcl86")]
+pub(crate) fn from_tcl_size(size: TclSize) -> usize {
+    usize::try_from(size).unwrap_or(0)
+}
+
+/// The Tcl functions used by [Object] and friends, which may be called
+/// without an interpreter at hand.
+///
+/// Tcl has a single stubs table per process, shared by all interpreters and
+/// (in threaded builds) all threads, so the first interpreter to initialize
+/// rtea fixes these for the lifetime of the process.  They are set exactly
+/// once, so reading them needs no synchronization.  The methods smooth over
+/// the differences between Tcl versions.
+#[cfg(feature = "tcl90")]
+pub(crate) struct GlobalFunctions {
+    pub(crate) stubs: usize,
+    pub(crate) alloc: extern "C" fn(usize) -> *mut c_void,
+    pub(crate) free: extern "C" fn(*mut c_void),
+    pub(crate) new_obj: extern "C" fn() -> *mut RawObject,
+    pub(crate) incr_ref_count: extern "C" fn(*mut RawObject),
+    pub(crate) decr_ref_count: extern "C" fn(*mut RawObject),
+    pub(crate) get_string: extern "C" fn(*mut RawObject) -> *mut c_char,
+    pub(crate) new_string_obj: extern "C" fn(*const c_char, usize) -> *mut RawObject,
+    pub(crate) set_string_obj: extern "C" fn(*mut RawObject, *const c_char, usize),
+    pub(crate) new_list_obj: extern "C" fn(usize, *const *mut RawObject) -> *mut RawObject,
+    pub(crate) scan_counted_element: extern "C" fn(*const c_char, usize, *mut c_int) -> usize,
+    pub(crate) convert_counted_element:
+        extern "C" fn(*const c_char, usize, *mut c_char, c_int) -> usize,
+}
+
+/// The Tcl functions used by [Object] and friends, which may be called
+/// without an interpreter at hand.
+///
+/// Tcl 8.6 implements `Tcl_IncrRefCount` and `Tcl_DecrRefCount` as macros,
+/// so the reference counts are managed through their debugging variants
+/// (which are plain functions in every build of Tcl).
+#[cfg(feature = "tcl86")]
+pub(crate) struct GlobalFunctions {
+    pub(crate) stubs: usize,
+    pub(crate) alloc: extern "C" fn(c_uint) -> *mut c_char,
+    pub(crate) free: extern "C" fn(*mut c_char),
+    pub(crate) new_obj: extern "C" fn() -> *mut RawObject,
+    pub(crate) incr_ref_count: extern "C" fn(*mut RawObject, *const c_char, c_int),
+    pub(crate) decr_ref_count: extern "C" fn(*mut RawObject, *const c_char, c_int),
+    pub(crate) get_string: extern "C" fn(*mut RawObject) -> *mut c_char,
+    pub(crate) new_string_obj: extern "C" fn(*const c_char, c_int) -> *mut RawObject,
+    pub(crate) set_string_obj: extern "C" fn(*mut RawObject, *const c_char, c_int),
+    pub(crate) new_list_obj: extern "C" fn(c_int, *const *mut RawObject) -> *mut RawObject,
+    pub(crate) scan_counted_element: extern "C" fn(*const c_char, c_int, *mut c_int) -> c_int,
+    pub(crate) convert_counted_element:
+        extern "C" fn(*const c_char, c_int, *mut c_char, c_int) -> c_int,
+}
+
+impl GlobalFunctions {
+    /// Allocates `size` bytes of Tcl-managed memory, returning null on
+    /// failure.
+    pub(crate) fn alloc(&self, size: usize) -> *mut c_void {
+        #[cfg(feature = "tcl86")]
+        let Ok(size) = c_uint::try_from(size) else {
+            return std::ptr::null_mut();
+        };
+        (self.alloc)(size).cast()
+    }
+
+    /// Frees memory allocated by Tcl.
+    pub(crate) fn free(&self, ptr: *mut c_void) {
+        (self.free)(ptr as _)
+    }
+
+    pub(crate) fn new_obj(&self) -> *mut RawObject {
+        (self.new_obj)()
+    }
+
+    #[cfg(feature = "tcl90")]
+    pub(crate) fn incr_ref_count(&self, obj: *mut RawObject) {
+        (self.incr_ref_count)(obj)
+    }
+
+    #[cfg(feature = "tcl86")]
+    pub(crate) fn incr_ref_count(&self, obj: *mut RawObject) {
+        (self.incr_ref_count)(obj, c"rtea".as_ptr(), 0)
+    }
+
+    #[cfg(feature = "tcl90")]
+    pub(crate) fn decr_ref_count(&self, obj: *mut RawObject) {
+        (self.decr_ref_count)(obj)
+    }
+
+    #[cfg(feature = "tcl86")]
+    pub(crate) fn decr_ref_count(&self, obj: *mut RawObject) {
+        (self.decr_ref_count)(obj, c"rtea".as_ptr(), 0)
+    }
+
+    pub(crate) fn get_string(&self, obj: *mut RawObject) -> *mut c_char {
+        (self.get_string)(obj)
+    }
+
+    pub(crate) fn new_string_obj(
+        &self,
+        bytes: *const c_char,
+        len: usize,
+    ) -> Result<*mut RawObject, Error> {
+        Ok((self.new_string_obj)(bytes, try_to_tcl_size(len)?))
+    }
+
+    pub(crate) fn set_string_obj(
+        &self,
+        obj: *mut RawObject,
+        bytes: *const c_char,
+        len: usize,
+    ) -> Result<(), Error> {
+        (self.set_string_obj)(obj, bytes, try_to_tcl_size(len)?);
+        Ok(())
+    }
+
+    pub(crate) fn new_list_obj(&self, objv: &[*mut RawObject]) -> Result<*mut RawObject, Error> {
+        Ok((self.new_list_obj)(
+            try_to_tcl_size(objv.len())?,
+            objv.as_ptr(),
+        ))
+    }
+
+    pub(crate) fn scan_counted_element(
+        &self,
+        src: *const c_char,
+        len: usize,
+        flags: &mut c_int,
+    ) -> Result<usize, Error> {
+        Ok(from_tcl_size((self.scan_counted_element)(
+            src,
+            try_to_tcl_size(len)?,
+            flags,
+        )))
+    }
+
+    pub(crate) fn convert_counted_element(
+        &self,
+        src: *const c_char,
+        len: usize,
+        dst: *mut u8,
+        flags: c_int,
+    ) -> usize {
+        from_tcl_size((self.convert_counted_element)(
+            src,
+            to_tcl_size(len),
+            dst as _,
+            flags,
+        ))
+    }
+}
+
+pub(crate) static GLOBAL_FUNCTIONS: OnceLock<GlobalFunctions> = OnceLock::new();
+
+/// Gets the global functions, which exist once any interpreter has been
+/// initialized.
+pub(crate) fn try_global_functions() -> Result<&'static GlobalFunctions, Error> {
+    GLOBAL_FUNCTIONS.get().ok_or(Error::NotInitialized)
+}
+
+/// Gets the global functions, panicking if no interpreter has been
+/// initialized.
+///
+/// Objects can only come from an interpreter, so this only fails if a Rust
+/// program creates objects before initializing an interpreter, which is a
+/// bug in that program.  Public functions that may be the first to reach
+/// this document it under `# Panics`, and fallible alternatives (like
+/// [Object::try_new_string](crate::Object::try_new_string)) use
+/// [try_global_functions] instead.
+pub(crate) fn global_functions() -> &'static GlobalFunctions {
+    try_global_functions().expect("rtea used before an interpreter was initialized")
+}
+
+/// Checks whether rtea's global functions have been initialized.
+///
+/// This is the case once [init_global_functions](crate::Interpreter::init_global_functions)
+/// has succeeded for any interpreter, which the
+/// [module_init](rtea_proc::module_init) macro does automatically.  Until
+/// then, creating an [Object](crate::Object) panics (or fails with
+/// [Error::NotInitialized] for the `try_` constructors).
+pub fn is_initialized() -> bool {
+    GLOBAL_FUNCTIONS.get().is_some()
+}
+
+/// Copies a string into memory allocated by Tcl, as used for the string
+/// representation of objects.
+///
+/// If Tcl cannot hold a copy of `rust_str` (e.g., it is larger than Tcl 8.6
+/// supports, or the allocation fails), a copy of a short message saying so
+/// is returned instead, so the result can always be handed to Tcl.
+///
+/// # Panics
+///
+/// Panics if no interpreter has been initialized yet, or if Tcl cannot even
+/// allocate the short message (where `Tcl_Alloc` would panic as well).
+pub fn tcl_string(rust_str: &str) -> (*mut c_char, TclSize) {
+    try_tcl_string(rust_str)
+        .or_else(|| try_tcl_string(TCL_STRING_FALLBACK))
+        .expect("Tcl failed to allocate a string")
+}
+
+/// Replaces strings that [tcl_string] cannot copy.
+const TCL_STRING_FALLBACK: &str = "string too large for Tcl";
+
+fn try_tcl_string(rust_str: &str) -> Option<(*mut c_char, TclSize)> {
+    let len = try_to_tcl_size(rust_str.len()).ok()?;
+    let tcl_buf = global_functions().alloc(rust_str.len() + 1) as *mut u8;
+    if tcl_buf.is_null() {
+        return None;
+    }
+
     unsafe {
-        {
-            let tcl_buf = ALLOC.expect("module not initialized")(tcl_alloc_len) as *mut u8;
-            let tcl_str = std::slice::from_raw_parts_mut(tcl_buf, tcl_alloc_len);
-            tcl_str[..rust_str.len()].copy_from_slice(rust_str.as_bytes());
-            if let Some(terminator) = tcl_str.last_mut() {
-                *terminator = 0;
-            }
-            (tcl_str.as_ptr() as *mut c_char, rust_str.len())
-        }
+        std::ptr::copy_nonoverlapping(rust_str.as_ptr(), tcl_buf, rust_str.len());
+        *tcl_buf.add(rust_str.len()) = 0;
     }
+    Some((tcl_buf as *mut c_char, len))
 }
@@ -0,0 +1,282 @@
+//! Helpers for testing extensions built with rtea.
+//!
+//! Enabled by the `testing` feature, which implies `embed` and is usually
+//! only turned on for tests:
+//!
+//! ```toml
+//! [dev-dependencies]
+//! rtea = { version = "0.3", features = ["testing"] }
+//! ```
+//!
+//! Each [TestInterpreter] is a fresh interpreter with its standard output
+//! and error captured in memory.  Tests run in parallel on separate threads,
+//! and Tcl keeps the standard channels per thread, so tests do not see each
+//! other's interpreters or output.  [assert_tcl_eq](crate::assert_tcl_eq)
+//! and [assert_tcl_error](crate::assert_tcl_error) check the outcome of
+//! scripts, and [TclTestSuite] runs test suites written with tcltest.
+//!
+//! # Example
+//!
+//! ```rust
+//! use rtea::testing::TestInterpreter;
+//! use rtea::*;
+//!
+//! #[module_init(Greeter, "1.0.0")]
+//! fn init(interp: &Interpreter) -> Result<TclStatus, String> {
+//!     interp.create_command("greet", greet)?;
+//!     Ok(TclStatus::Ok)
+//! }
+//!
+//! fn greet(interp: &Interpreter, args: Vec<&str>) -> Result<TclStatus, String> {
+//!     let name = args.get(1).ok_or("usage: greet name")?;
+//!     interp.set_result(&format!("Hello, {}!", name));
+//!     Ok(TclStatus::Ok)
+//! }
+//!
+//! let interp = TestInterpreter::new();
+//! interp.init(Greeter_Init).unwrap();
+//!
+//! assert_tcl_eq!(interp, "greet world", "Hello, world!");
+//! assert_tcl_error!(interp, "greet");
+//! assert_tcl_error!(interp, "expr {1 / 0}", "ARITH DIVZERO");
+//!
+//! interp.eval("puts [greet Tcl]").unwrap();
+//! assert_eq!(interp.take_stdout(), "Hello, Tcl!\n");
+//! ```
+
+use std::ops::Deref;
+
+mod tcltest;
+
+pub use tcltest::*;
+
+use crate::Interpreter;
+use crate::Object;
+use crate::OwnedInterpreter;
+use crate::TclStatus;
+use crate::interpreter::CapturedChannel;
+use crate::interpreter::StdChannel;
+
+/// The initialization function of an extension, as created by
+/// [module_init](rtea_proc::module_init).
+pub type InitProc = extern "C" fn(*const Interpreter) -> TclStatus;
+
+/// A fresh interpreter for a test.
+///
+/// What scripts write to `stdout` and `stderr` is captured rather than
+/// printed, and can be read with [stdout](TestInterpreter::stdout) and
+/// [stderr](TestInterpreter::stderr).  The capturing channels are handled
+/// by commands in the `::rtea::testing` namespace.  The interpreter
+/// dereferences to [Interpreter] and is deleted when dropped.
+pub struct TestInterpreter {
+    // Declared before `interp` so the channels are restored first.
+    stdout: CapturedChannel,
+    stderr: CapturedChannel,
+    interp: OwnedInterpreter,
+}
+
+impl TestInterpreter {
+    /// Creates an interpreter without Tcl's script library.
+    ///
+    /// All built-in commands are available, but `package require` is not
+    /// (see [with_library](TestInterpreter::with_library)).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the interpreter cannot be created.
+    pub fn new() -> TestInterpreter {
+        let interp = OwnedInterpreter::without_init()
+            .unwrap_or_else(|err| panic!("cannot create a Tcl interpreter: {}", err));
+        TestInterpreter::capture(interp)
+    }
+
+    /// Creates an interpreter and runs `Tcl_Init` on it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the interpreter cannot be created or Tcl's script library
+    /// cannot be found.
+    pub fn with_library() -> TestInterpreter {
+        let interp = OwnedInterpreter::new()
+            .unwrap_or_else(|err| panic!("cannot create a Tcl interpreter: {}", err));
+        TestInterpreter::capture(interp)
+    }
+
+    fn capture(interp: OwnedInterpreter) -> TestInterpreter {
+        let capture = |which| {
+            interp
+                .capture_std_channel(which)
+                .unwrap_or_else(|err| panic!("cannot capture {:?}: {}", which, err))
+        };
+        TestInterpreter {
+            stdout: capture(StdChannel::Stdout),
+            stderr: capture(StdChannel::Stderr),
+            interp,
+        }
+    }
+
+    /// Runs an extension's initialization function (`*_Init`) on the
+    /// interpreter.
+    ///
+    /// The error message left by a failed initialization is returned as
+    /// `Err`.
+    pub fn init(&self, init: InitProc) -> Result<(), String> {
+        match init(self.interp.as_ptr()) {
+            TclStatus::Ok => Ok(()),
+            _ => Err(self.get_obj_result().get_string().to_string()),
+        }
+    }
+
+    /// Gets what was written to `stdout` so far.
+    pub fn stdout(&self) -> String {
+        self.stdout.contents()
+    }
+
+    /// Gets what was written to `stderr` so far.
+    pub fn stderr(&self) -> String {
+        self.stderr.contents()
+    }
+
+    /// Gets what was written to `stdout` so far and clears it.
+    pub fn take_stdout(&self) -> String {
+        self.stdout.take()
+    }
+
+    /// Gets what was written to `stderr` so far and clears it.
+    pub fn take_stderr(&self) -> String {
+        self.stderr.take()
+    }
+}
+
+impl Default for TestInterpreter {
+    fn default() -> TestInterpreter {
+        TestInterpreter::new()
+    }
+}
+
+impl Deref for TestInterpreter {
+    type Target = Interpreter;
+
+    fn deref(&self) -> &Interpreter {
+        &self.interp
+    }
+}
+
+impl AsRef<Interpreter> for TestInterpreter {
+    fn as_ref(&self) -> &Interpreter {
+        self
+    }
+}
+
+/// Implements [assert_tcl_eq](crate::assert_tcl_eq).
+#[doc(hidden)]
+#[track_caller]
+pub fn check_eq(interp: &Interpreter, script: &str, expected: &str) {
+    match interp.eval(script) {
+        Ok(result) => assert_eq!(
+            result.get_string(),
+            expected,
+            "unexpected result of script `{}`",
+            script
+        ),
+        Err(err) => panic!(
+            "script `{}` failed: {}\n{}",
+            script,
+            err.get_string(),
+            global(interp, "::errorInfo")
+        ),
+    }
+}
+
+/// Implements [assert_tcl_error](crate::assert_tcl_error).
+#[doc(hidden)]
+#[track_caller]
+pub fn check_error(interp: &Interpreter, script: &str, error_code: Option<&str>) {
+    if let Ok(result) = interp.eval(script) {
+        panic!(
+            "script `{}` succeeded with `{}` instead of failing",
+            script,
+            result.get_string()
+        );
+    }
+
+    let Some(expected) = error_code else {
+        return;
+    };
+    let actual = global(interp, "::errorCode");
+    let elements = |code: &str| {
+        interp
+            .list_elements(&Object::new_string(code))
+            .unwrap_or_else(|err| panic!("invalid error code `{}`: {}", code, err.get_string()))
+            .iter()
+            .map(|element| element.get_string().to_string())
+            .collect::<Vec<_>>()
+    };
+    assert!(
+        elements(&actual).starts_with(&elements(expected)),
+        "script `{}` failed with error code `{}` instead of `{}`",
+        script,
+        actual,
+        expected
+    );
+}
+
+fn global(interp: &Interpreter, name: &str) -> String {
+    interp
+        .get_var(&Object::new_string(name))
+        .map(|value| value.get_string().to_string())
+        .unwrap_or_default()
+}
+
+/// Asserts that a script succeeds with the expected result.
+///
+/// The interpreter may be given as a [TestInterpreter] or any other value
+/// dereferencing to an [Interpreter].  If the script fails, the panic
+/// message includes Tcl's stack trace.
+///
+/// ```rust
+/// # use rtea::*;
+/// # let interp = rtea::testing::TestInterpreter::new();
+/// assert_tcl_eq!(interp, "expr {6 * 7}", "42");
+/// ```
+#[macro_export]
+macro_rules! assert_tcl_eq {
+    ($interp:expr, $script:expr, $expected:expr $(,)?) => {
+        $crate::testing::check_eq(
+            &$interp,
+            ::core::convert::AsRef::<str>::as_ref(&$script),
+            ::core::convert::AsRef::<str>::as_ref(&$expected),
+        )
+    };
+}
+
+/// Asserts that a script fails, optionally with a given error code.
+///
+/// The error code is a Tcl list (as set with `return -errorcode` and
+/// stored in `::errorCode`).  It matches if it is a prefix of the actual
+/// error code, so `"ARITH DIVZERO"` matches
+/// `ARITH DIVZERO {divide by zero}`.
+///
+/// ```rust
+/// # use rtea::*;
+/// # let interp = rtea::testing::TestInterpreter::new();
+/// assert_tcl_error!(interp, "error oops");
+/// assert_tcl_error!(interp, "error oops {} {MY ERROR 1}", "MY ERROR");
+/// ```
+#[macro_export]
+macro_rules! assert_tcl_error {
+    ($interp:expr, $script:expr $(,)?) => {
+        $crate::testing::check_error(
+            &$interp,
+            ::core::convert::AsRef::<str>::as_ref(&$script),
+            ::core::option::Option::None,
+        )
+    };
+    ($interp:expr, $script:expr, $error_code:expr $(,)?) => {
+        $crate::testing::check_error(
+            &$interp,
+            ::core::convert::AsRef::<str>::as_ref(&$script),
+            ::core::option::Option::Some(::core::convert::AsRef::<str>::as_ref(&$error_code)),
+        )
+    };
+}
@@ -0,0 +1,563 @@
+//! Running [tcltest](https://www.tcl.tk/man/tcl/TclCmd/tcltest.html) suites
+//! as part of `cargo test`.
+//!
+//! Each `.test` file is sourced in its own [TestInterpreter], after the
+//! extension's initialization functions have run in it.  `tcltest::test`
+//! is wrapped so that the outcome of every case is recorded, and the cases
+//! are reported the way the standard test harness reports Rust tests.
+
+use std::fmt::Display;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+use super::InitProc;
+use super::TestInterpreter;
+use crate::Object;
+use crate::quote_element;
+
+/// Loads tcltest and wraps `tcltest::test` to record each case as
+/// `{name outcome}` in `::rtea::tcltest::results`.
+///
+/// The wrapped command stays in the `tcltest` namespace (under a name that
+/// is not exported), as it relies on the namespace's variables.  Unless
+/// `::rtea::tcltest::mode` is `run`, the selected cases are only recorded
+/// (as `listed`, or as `filtered` for `ignored`) without running them.
+const SETUP: &str = r#"
+package require tcltest 2
+interp hide {} exit
+namespace eval ::rtea::tcltest {
+    variable results {}
+    variable filters {}
+    variable skips {}
+    variable exact 0
+    variable mode run
+
+    proc selected {name} {
+        variable file
+        variable filters
+        variable skips
+        variable exact
+        set id ${file}::$name
+        foreach skip $skips {
+            if {$exact ? $id eq $skip : [string first $skip $id] >= 0} {
+                return 0
+            }
+        }
+        if {![llength $filters]} {
+            return 1
+        }
+        foreach filter $filters {
+            if {$exact ? $id eq $filter : [string first $filter $id] >= 0} {
+                return 1
+            }
+        }
+        return 0
+    }
+}
+rename ::tcltest::test ::tcltest::RteaWrappedTest
+proc ::tcltest::test {name args} {
+    variable numTests
+    if {![::rtea::tcltest::selected $name]} {
+        lappend ::rtea::tcltest::results [list $name filtered]
+        return
+    }
+    switch -- $::rtea::tcltest::mode {
+        list {
+            lappend ::rtea::tcltest::results [list $name listed]
+            return
+        }
+        ignored {
+            lappend ::rtea::tcltest::results [list $name filtered]
+            return
+        }
+    }
+    set failed $numTests(Failed)
+    set skipped $numTests(Skipped)
+    uplevel 1 [list ::tcltest::RteaWrappedTest $name {*}$args]
+    if {$numTests(Failed) > $failed} {
+        set outcome failed
+    } elseif {$numTests(Skipped) > $skipped} {
+        set outcome skipped
+    } else {
+        set outcome passed
+    }
+    lappend ::rtea::tcltest::results [list $name $outcome]
+}
+"#;
+
+/// The outcome of a tcltest case.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TclTestOutcome {
+    Passed,
+    Failed,
+    /// The case was skipped because of its constraints.
+    Skipped,
+    /// The case did not match the filters of the run.
+    Filtered,
+    /// The case was [listed](TclTestSuite::list) without running it.
+    Listed,
+}
+
+/// A tcltest case that was run (or skipped).
+#[derive(Debug, Clone)]
+pub struct TclTestCase {
+    /// The name of the `.test` file.
+    pub file: String,
+    /// The name of the case, or the file's name for errors outside of any
+    /// case (e.g., a syntax error in the file).
+    pub name: String,
+    pub outcome: TclTestOutcome,
+    /// What tcltest reported about a failure.
+    pub output: String,
+}
+
+impl TclTestCase {
+    /// Gets the name the case is reported and filtered as
+    /// (`file.test::name`).
+    pub fn id(&self) -> String {
+        if self.name == self.file {
+            self.file.clone()
+        } else {
+            format!("{}::{}", self.file, self.name)
+        }
+    }
+}
+
+/// The cases of a [TclTestSuite] run.
+#[derive(Debug, Clone, Default)]
+pub struct TclTestReport {
+    pub cases: Vec<TclTestCase>,
+}
+
+impl TclTestReport {
+    /// Counts the cases with the given outcome.
+    pub fn count(&self, outcome: TclTestOutcome) -> usize {
+        self.cases
+            .iter()
+            .filter(|case| case.outcome == outcome)
+            .count()
+    }
+
+    /// Iterates over the failed cases.
+    pub fn failures(&self) -> impl Iterator<Item = &TclTestCase> {
+        self.cases
+            .iter()
+            .filter(|case| case.outcome == TclTestOutcome::Failed)
+    }
+
+    /// Checks whether no case failed.
+    pub fn success(&self) -> bool {
+        self.failures().next().is_none()
+    }
+
+    /// Panics if any case failed, listing the failures.
+    #[track_caller]
+    pub fn assert_success(&self) {
+        if !self.success() {
+            panic!("tcltest failures:\n{}", self);
+        }
+    }
+}
+
+impl Display for TclTestReport {
+    /// Formats the details of the failures and the summary line, as the
+    /// standard test harness does.
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if !self.success() {
+            writeln!(f, "\nfailures:\n")?;
+            for case in self.failures() {
+                writeln!(f, "---- {} ----\n{}", case.id(), case.output)?;
+            }
+            writeln!(f, "\nfailures:")?;
+            for case in self.failures() {
+                writeln!(f, "    {}", case.id())?;
+            }
+        }
+
+        write!(
+            f,
+            "\ntest result: {}. {} passed; {} failed; {} ignored; 0 measured; {} filtered out",
+            if self.success() { "ok" } else { "FAILED" },
+            self.count(TclTestOutcome::Passed),
+            self.count(TclTestOutcome::Failed),
+            self.count(TclTestOutcome::Skipped),
+            self.count(TclTestOutcome::Filtered),
+        )
+    }
+}
+
+/// A directory of tcltest `.test` files to run against an extension.
+///
+/// The suite is run either from a regular `#[test]` function with
+/// [run](TclTestSuite::run), or as a whole test target with
+/// [main](TclTestSuite::main) (see [tcltest_main](crate::tcltest_main)),
+/// which reports each case on its own line of the `cargo test` output.
+///
+/// The test files find the extension already loaded, so their usual
+/// `package require` of it succeeds.  Each file runs in a fresh
+/// interpreter with Tcl's script library, and `exit` is hidden so that a
+/// test file cannot end the test process.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use rtea::testing::TclTestSuite;
+/// use rtea::*;
+///
+/// #[module_init(Example, "1.0.0")]
+/// fn init(interp: &Interpreter) -> Result<TclStatus, String> {
+///     Ok(TclStatus::Ok)
+/// }
+///
+/// #[test]
+/// fn tcltest() {
+///     TclTestSuite::new("tests/tcl")
+///         .init(Example_Init)
+///         .run()
+///         .assert_success();
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct TclTestSuite {
+    dir: PathBuf,
+    inits: Vec<InitProc>,
+    filters: Vec<String>,
+    skips: Vec<String>,
+    exact: bool,
+    ignored: bool,
+}
+
+/// How `run_file` handles the selected cases, set as
+/// `::rtea::tcltest::mode`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Run,
+    List,
+    Ignored,
+}
+
+impl Mode {
+    fn name(self) -> &'static str {
+        match self {
+            Mode::Run => "run",
+            Mode::List => "list",
+            Mode::Ignored => "ignored",
+        }
+    }
+}
+
+impl TclTestSuite {
+    /// Creates a suite running the `.test` files in `dir`.
+    pub fn new<P: AsRef<Path>>(dir: P) -> TclTestSuite {
+        TclTestSuite {
+            dir: dir.as_ref().to_path_buf(),
+            inits: Vec::new(),
+            filters: Vec::new(),
+            skips: Vec::new(),
+            exact: false,
+            ignored: false,
+        }
+    }
+
+    /// Adds an initialization function (`*_Init`) to run before each file.
+    pub fn init(mut self, init: InitProc) -> TclTestSuite {
+        self.inits.push(init);
+        self
+    }
+
+    /// Only runs the cases whose [id](TclTestCase::id) contains `filter`.
+    ///
+    /// Without filters, all cases run.
+    pub fn filter(mut self, filter: &str) -> TclTestSuite {
+        self.filters.push(filter.to_string());
+        self
+    }
+
+    /// Runs the suite, printing a line for each case.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the directory cannot be read or an interpreter cannot be
+    /// set up.
+    pub fn run(&self) -> TclTestReport {
+        let files = self.files();
+        let tmpdir = tmpdir();
+        let mode = if self.ignored {
+            Mode::Ignored
+        } else {
+            Mode::Run
+        };
+        println!("\nrunning {} tcltest files", files.len());
+        let mut report = TclTestReport::default();
+        for file in files {
+            for case in self.run_file(&file, &tmpdir, mode) {
+                match case.outcome {
+                    TclTestOutcome::Passed => println!("test {} ... ok", case.id()),
+                    TclTestOutcome::Failed => println!("test {} ... FAILED", case.id()),
+                    TclTestOutcome::Skipped => println!("test {} ... ignored", case.id()),
+                    TclTestOutcome::Filtered | TclTestOutcome::Listed => {}
+                }
+                report.cases.push(case);
+            }
+        }
+        let _ = std::fs::remove_dir_all(&tmpdir);
+
+        report
+    }
+
+    /// Lists the cases the suite would run, without running them.
+    ///
+    /// The `.test` files are still sourced, so code outside of the cases
+    /// runs, but the bodies of the cases do not.  The returned cases have
+    /// the [Listed](TclTestOutcome::Listed) outcome.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the directory cannot be read or an interpreter cannot be
+    /// set up.
+    pub fn list(&self) -> Vec<TclTestCase> {
+        let tmpdir = tmpdir();
+        let cases = self
+            .files()
+            .iter()
+            .flat_map(|file| self.run_file(file, &tmpdir, Mode::List))
+            .filter(|case| case.outcome == TclTestOutcome::Listed)
+            .collect();
+        let _ = std::fs::remove_dir_all(&tmpdir);
+
+        cases
+    }
+
+    /// Runs the suite as the `main` function of a test target, then exits.
+    ///
+    /// The target must be declared with `harness = false` in `Cargo.toml`.
+    /// The arguments given to `cargo test` are honored the way the standard
+    /// harness does for filters, `--skip`, `--exact` and `--list` (which
+    /// prints `<id>: test` for each case without running any).  Cases are
+    /// only ignored when tcltest skips them for unmet constraints, which
+    /// cannot be forced to run, so `--ignored` runs no case and
+    /// `--include-ignored` runs the same cases as a plain run.  Other
+    /// options are ignored.  The process exits with a failure status if any
+    /// case failed.
+    pub fn main(mut self) -> ! {
+        let mut list = false;
+        let mut args = std::env::args().skip(1);
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--exact" => self.exact = true,
+                "--skip" => self.skips.extend(args.next()),
+                "--list" => list = true,
+                "--ignored" => self.ignored = true,
+                "--include-ignored" => {}
+                // Options taking a value.
+                "--test-threads" | "--color" | "--format" | "--logfile" | "-Z" => {
+                    args.next();
+                }
+                _ if arg.starts_with('-') => {}
+                _ => self.filters.push(arg),
+            }
+        }
+
+        if list {
+            let cases = if self.ignored {
+                Vec::new()
+            } else {
+                self.list()
+            };
+            for case in &cases {
+                println!("{}: test", case.id());
+            }
+            println!("\n{}, 0 benchmarks", plural(cases.len(), "test"));
+            std::process::exit(0);
+        }
+
+        let report = self.run();
+        println!("{}\n", report);
+        std::process::exit(if report.success() { 0 } else { 101 });
+    }
+
+    /// Gets the `.test` files of the suite, sorted by name.
+    fn files(&self) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = std::fs::read_dir(&self.dir)
+            .unwrap_or_else(|err| panic!("cannot read {}: {}", self.dir.display(), err))
+            .map(|entry| entry.expect("cannot read directory entry").path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "test"))
+            .collect();
+        files.sort();
+        files
+    }
+
+    fn run_file(&self, path: &Path, tmpdir: &Path, mode: Mode) -> Vec<TclTestCase> {
+        let file = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let interp = TestInterpreter::with_library();
+        let failure = |output: String| TclTestCase {
+            file: file.clone(),
+            name: file.clone(),
+            outcome: TclTestOutcome::Failed,
+            output,
+        };
+
+        for init in &self.inits {
+            if let Err(err) = interp.init(*init) {
+                return vec![failure(format!("initialization failed: {}", err))];
+            }
+        }
+        let mut setup = SETUP.to_string();
+        setup.push_str(&format!(
+            "set ::rtea::tcltest::file {}\n\
+             set ::rtea::tcltest::filters {}\n\
+             set ::rtea::tcltest::skips {}\n\
+             set ::rtea::tcltest::exact {}\n\
+             set ::rtea::tcltest::mode {}\n\
+             tcltest::configure -testdir {} -tmpdir {}\n",
+            quote_element(&file),
+            list(&self.filters),
+            list(&self.skips),
+            self.exact as u8,
+            mode.name(),
+            quote_element(&self.dir.to_string_lossy()),
+            quote_element(&tmpdir.to_string_lossy()),
+        ));
+        if let Err(err) = interp.eval(&setup) {
+            return vec![failure(format!(
+                "cannot set up tcltest: {}",
+                err.get_string()
+            ))];
+        }
+
+        let sourced = interp.eval_file(path, None);
+        let output = interp.take_stdout() + &interp.take_stderr();
+        let results = interp
+            .get_var(&Object::new_string("::rtea::tcltest::results"))
+            .and_then(|results| interp.list_elements(&results))
+            .unwrap_or_default();
+
+        let mut cases: Vec<TclTestCase> = results
+            .iter()
+            .filter_map(|result| {
+                let result = interp.list_elements(result).ok()?;
+                let name = result.first()?.get_string().to_string();
+                let outcome = match result.get(1)?.get_string() {
+                    "passed" => TclTestOutcome::Passed,
+                    "failed" => TclTestOutcome::Failed,
+                    "skipped" => TclTestOutcome::Skipped,
+                    "listed" => TclTestOutcome::Listed,
+                    _ => TclTestOutcome::Filtered,
+                };
+                let output = if outcome == TclTestOutcome::Failed {
+                    failure_output(&output, &name)
+                } else {
+                    String::new()
+                };
+                Some(TclTestCase {
+                    file: file.clone(),
+                    name,
+                    outcome,
+                    output,
+                })
+            })
+            .collect();
+
+        if let Err(err) = sourced {
+            let error_info = err.error_info().map_or("", |info| info.get_string());
+            cases.push(failure(format!("{}\n{}", error_info, output)));
+        }
+
+        cases
+    }
+}
+
+/// Creates a temporary directory name for a run of a suite.
+fn tmpdir() -> PathBuf {
+    // Suites may run in parallel, each with its own temporary directory.
+    static RUNS: AtomicUsize = AtomicUsize::new(0);
+    std::env::temp_dir().join(format!(
+        "rtea-tcltest-{}-{}",
+        std::process::id(),
+        RUNS.fetch_add(1, Ordering::Relaxed)
+    ))
+}
+
+/// Formats a count the way the standard harness does (`1 test`, `2 tests`).
+fn plural(count: usize, noun: &str) -> String {
+    if count == 1 {
+        format!("1 {}", noun)
+    } else {
+        format!("{} {}s", count, noun)
+    }
+}
+
+/// Formats strings as a Tcl list.
+fn list(elements: &[String]) -> String {
+    elements
+        .iter()
+        .map(|element| quote_element(element))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Extracts what tcltest printed about the failure of the case `name`,
+/// which starts with a `==== name description FAILED` line and ends with a
+/// `==== name FAILED` line.
+fn failure_output(output: &str, name: &str) -> String {
+    let start = format!("==== {} ", name);
+    let end = format!("==== {} FAILED", name);
+    let mut lines = Vec::new();
+    for line in output.lines() {
+        if lines.is_empty() {
+            if line.starts_with(&start) && line.ends_with(" FAILED") && line != end {
+                lines.push(line);
+            }
+        } else if line == end {
+            lines.push(line);
+            break;
+        } else {
+            lines.push(line);
+        }
+    }
+    lines.join("\n")
+}
+
+/// Defines `main` for a test target running a tcltest suite.
+///
+/// The first argument is the directory of the `.test` files, relative to
+/// the crate's manifest, and the rest are the extension's initialization
+/// functions.  The test target must be declared with `harness = false`:
+///
+/// ```toml
+/// [[test]]
+/// name = "tcl"
+/// harness = false
+/// ```
+///
+/// ```rust,ignore
+/// // tests/tcl.rs
+/// use rtea::*;
+///
+/// #[module_init(Example, "1.0.0")]
+/// fn init(interp: &Interpreter) -> Result<TclStatus, String> {
+///     Ok(TclStatus::Ok)
+/// }
+///
+/// rtea::tcltest_main!("tests/tcl", Example_Init);
+/// ```
+///
+/// Each tcltest case is then reported as a test by `cargo test`.  See
+/// [TclTestSuite::main].
+#[macro_export]
+macro_rules! tcltest_main {
+    ($dir:expr $(, $init:expr)* $(,)?) => {
+        fn main() {
+            $crate::testing::TclTestSuite::new(
+                ::std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join($dir),
+            )
+            $(.init($init))*
+            .main()
+        }
+    };
+}
@@ -0,0 +1,947 @@
+//! Generates the Rust declarations of Tcl's stubs tables from `.decls` files.
+//!
+//! The `.decls` files are the Tcl scripts Tcl's own `genStubs.tcl` reads to
+//! produce `tclDecls.h` and friends.  Each `interface` becomes a `#[repr(C)]`
+//! struct with one field per slot, typed after the C declaration in that
+//! slot.  The platform selects which `unix`, `win` or `macosx` declarations
+//! fill the platform specific slots, following the same rules as
+//! `genStubs.tcl`.
+//!
+//! Types the declarations use are emitted alongside the tables: handles
+//! (like `Tcl_Channel`) as pointers to opaque structs, structs (like
+//! `Tcl_HashTable`) as opaque structs, and callbacks (like `Tcl_ObjCmdProc`)
+//! as `extern "C" fn` types.  A type the generator does not know is an error
+//! rather than being passed as `c_void`.
+//!
+//! The generated code expects `Interpreter`, `ObjectType`, `RawObject` and
+//! `TclSize` to be in scope where it is included.
+
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+/// The platform to lay out the stubs tables for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Platform {
+    Unix,
+    Win,
+    MacOsX,
+}
+
+impl Platform {
+    /// Gets the platform of a Rust `target_os`.
+    pub fn from_target_os(os: &str) -> Platform {
+        match os {
+            "windows" => Platform::Win,
+            "macos" => Platform::MacOsX,
+            _ => Platform::Unix,
+        }
+    }
+}
+
+/// C types that are plain aliases of other C types.
+const ALIASES: &[(&str, &str)] = &[
+    ("ClientData", "void *"),
+    ("Tcl_WideInt", "long long"),
+    ("Tcl_WideUInt", "unsigned long long"),
+    ("Tcl_PathType", "int"),
+    ("Tcl_QueuePosition", "int"),
+    ("Tcl_ValueType", "int"),
+    ("TclPlatformType", "int"),
+    ("mode_t", "unsigned int"),
+    ("time_t", "long"),
+    ("va_list", "void *"),
+    ("in_addr", "unsigned int"),
+    ("DWORD", "unsigned long"),
+    ("HANDLE", "void *"),
+    ("HINSTANCE", "void *"),
+    ("SOCKET", "size_t"),
+    ("TCHAR", "unsigned short"),
+];
+
+/// C scalar types and their Rust equivalents.
+const SCALARS: &[(&str, &str)] = &[
+    ("char", "c_char"),
+    ("signed char", "c_schar"),
+    ("unsigned char", "c_uchar"),
+    ("short", "c_short"),
+    ("unsigned short", "c_ushort"),
+    ("int", "c_int"),
+    ("unsigned", "c_uint"),
+    ("unsigned int", "c_uint"),
+    ("long", "c_long"),
+    ("unsigned long", "c_ulong"),
+    ("long long", "c_longlong"),
+    ("unsigned long long", "c_ulonglong"),
+    ("float", "c_float"),
+    ("double", "c_double"),
+    ("size_t", "usize"),
+    ("Tcl_Size", "TclSize"),
+];
+
+/// Handles: pointers to structs Tcl never defines.
+const HANDLES: &[&str] = &[
+    "Tcl_AsyncHandler",
+    "Tcl_Channel",
+    "Tcl_ChannelTypeVersion",
+    "Tcl_Command",
+    "Tcl_Condition",
+    "Tcl_Encoding",
+    "Tcl_EncodingState",
+    "Tcl_InterpState",
+    "Tcl_LoadHandle",
+    "Tcl_Mutex",
+    "Tcl_Pid",
+    "Tcl_RegExp",
+    "Tcl_ThreadDataKey",
+    "Tcl_ThreadId",
+    "Tcl_TimerToken",
+    "Tcl_Trace",
+    "Tcl_Var",
+    "Tcl_ZlibStream",
+    "TclFile",
+    "TclHandle",
+];
+
+/// Structs only ever passed by pointer, whose fields rtea does not access.
+const OPAQUES: &[&str] = &[
+    "Tcl_ArgvInfo",
+    "Tcl_CallFrame",
+    "Tcl_ChannelType",
+    "Tcl_CmdInfo",
+    "Tcl_Config",
+    "Tcl_DString",
+    "Tcl_DictSearch",
+    "Tcl_DirEntry",
+    "Tcl_EncodingType",
+    "Tcl_Event",
+    "Tcl_Filesystem",
+    "Tcl_GlobTypeData",
+    "Tcl_HashEntry",
+    "Tcl_HashKeyType",
+    "Tcl_HashSearch",
+    "Tcl_HashTable",
+    "Tcl_Namespace",
+    "Tcl_NotifierProcs",
+    "Tcl_ObjInternalRep",
+    "Tcl_Parse",
+    "Tcl_RegExpInfo",
+    "Tcl_ResolvedVarInfo",
+    "Tcl_ResolverInfo",
+    "Tcl_SavedResult",
+    "Tcl_StatBuf",
+    "Tcl_Time",
+    "Tcl_Token",
+    "Tcl_Value",
+    "mp_int",
+    "AuxDataType",
+    "ByteCode",
+    "CallFrame",
+    "CmdFrame",
+    "Command",
+    "CompileEnv",
+    "ExecEnv",
+    "Interp",
+    "LiteralEntry",
+    "LiteralTable",
+    "Namespace",
+    "NRE_callback",
+    "Proc",
+    "ResolverScheme",
+    "TclDIR",
+    "TclVarHashTable",
+    "Var",
+    "FILE",
+    "servent",
+    "stat",
+    "tm",
+    "utimbuf",
+];
+
+/// The prototypes of the callbacks (`typedef`s of function types).
+const CALLBACKS: &[&str] = &[
+    "int Tcl_AppInitProc(Tcl_Interp *interp)",
+    "int Tcl_ArgvFuncProc(ClientData clientData, Tcl_Obj *objPtr, void *dstPtr)",
+    "int Tcl_ArgvGenFuncProc(ClientData clientData, Tcl_Interp *interp, int objc, \
+     Tcl_Obj *const *objv, void *dstPtr)",
+    "int Tcl_AsyncProc(ClientData clientData, Tcl_Interp *interp, int code)",
+    "void Tcl_ChannelProc(ClientData clientData, int mask)",
+    "void Tcl_CloseProc(ClientData data)",
+    "void Tcl_CmdDeleteProc(ClientData clientData)",
+    "int Tcl_CmdObjTraceProc(ClientData clientData, Tcl_Interp *interp, int level, \
+     const char *command, Tcl_Command commandInfo, int objc, Tcl_Obj *const *objv)",
+    "int Tcl_CmdObjTraceProc2(ClientData clientData, Tcl_Interp *interp, Tcl_Size level, \
+     const char *command, Tcl_Command commandInfo, Tcl_Size objc, Tcl_Obj *const *objv)",
+    "void Tcl_CmdObjTraceDeleteProc(ClientData clientData)",
+    "int Tcl_CmdProc(ClientData clientData, Tcl_Interp *interp, int argc, const char *argv[])",
+    "void Tcl_CmdTraceProc(ClientData clientData, Tcl_Interp *interp, int level, \
+     char *command, Tcl_CmdProc *proc, ClientData cmdClientData, int argc, const char *argv[])",
+    "void Tcl_CommandTraceProc(ClientData clientData, Tcl_Interp *interp, \
+     const char *oldName, const char *newName, int flags)",
+    "int Tcl_DriverBlockModeProc(ClientData instanceData, int mode)",
+    "int Tcl_DriverCloseProc(ClientData instanceData, Tcl_Interp *interp)",
+    "int Tcl_DriverClose2Proc(ClientData instanceData, Tcl_Interp *interp, int flags)",
+    "int Tcl_DriverFlushProc(ClientData instanceData)",
+    "int Tcl_DriverGetHandleProc(ClientData instanceData, int direction, \
+     ClientData *handlePtr)",
+    "int Tcl_DriverGetOptionProc(ClientData instanceData, Tcl_Interp *interp, \
+     const char *optionName, Tcl_DString *dsPtr)",
+    "int Tcl_DriverHandlerProc(ClientData instanceData, int interestMask)",
+    "int Tcl_DriverInputProc(ClientData instanceData, char *buf, int toRead, \
+     int *errorCodePtr)",
+    "int Tcl_DriverOutputProc(ClientData instanceData, const char *buf, int toWrite, \
+     int *errorCodePtr)",
+    "int Tcl_DriverSeekProc(ClientData instanceData, long offset, int mode, \
+     int *errorCodePtr)",
+    "int Tcl_DriverSetOptionProc(ClientData instanceData, Tcl_Interp *interp, \
+     const char *optionName, const char *value)",
+    "void Tcl_DriverThreadActionProc(ClientData instanceData, int action)",
+    "int Tcl_DriverTruncateProc(ClientData instanceData, Tcl_WideInt length)",
+    "void Tcl_DriverWatchProc(ClientData instanceData, int mask)",
+    "Tcl_WideInt Tcl_DriverWideSeekProc(ClientData instanceData, Tcl_WideInt offset, \
+     int mode, int *errorCodePtr)",
+    "void Tcl_DupInternalRepProc(Tcl_Obj *srcPtr, Tcl_Obj *dupPtr)",
+    "int Tcl_EncodingConvertProc(ClientData clientData, const char *src, int srcLen, \
+     int flags, Tcl_EncodingState *statePtr, char *dst, int dstLen, int *srcReadPtr, \
+     int *dstWrotePtr, int *dstCharsPtr)",
+    "void Tcl_EncodingFreeProc(ClientData clientData)",
+    "void Tcl_EventCheckProc(ClientData clientData, int flags)",
+    "int Tcl_EventDeleteProc(Tcl_Event *evPtr, ClientData clientData)",
+    "int Tcl_EventProc(Tcl_Event *evPtr, int flags)",
+    "void Tcl_EventSetupProc(ClientData clientData, int flags)",
+    "void Tcl_ExitProc(ClientData clientData)",
+    "void Tcl_FileProc(ClientData clientData, int mask)",
+    "void Tcl_FreeInternalRepProc(Tcl_Obj *objPtr)",
+    "void Tcl_FreeProc(char *blockPtr)",
+    "void Tcl_FSUnloadFileProc(Tcl_LoadHandle loadHandle)",
+    "void Tcl_GetTimeProc(Tcl_Time *timebuf, ClientData clientData)",
+    "void Tcl_IdleProc(ClientData clientData)",
+    "void Tcl_InterpDeleteProc(ClientData clientData, Tcl_Interp *interp)",
+    "void Tcl_LimitHandlerDeleteProc(ClientData clientData)",
+    "void Tcl_LimitHandlerProc(ClientData clientData, Tcl_Interp *interp)",
+    "void Tcl_MainLoopProc(void)",
+    "int Tcl_MathProc(ClientData clientData, Tcl_Interp *interp, Tcl_Value *args, \
+     Tcl_Value *resultPtr)",
+    "void Tcl_NamespaceDeleteProc(ClientData clientData)",
+    "int Tcl_NRPostProc(ClientData data[], Tcl_Interp *interp, int result)",
+    "int Tcl_ObjCmdProc(ClientData clientData, Tcl_Interp *interp, int objc, \
+     Tcl_Obj *const *objv)",
+    "int Tcl_ObjCmdProc2(ClientData clientData, Tcl_Interp *interp, Tcl_Size objc, \
+     Tcl_Obj *const *objv)",
+    "int Tcl_PackageInitProc(Tcl_Interp *interp)",
+    "int Tcl_PackageUnloadProc(Tcl_Interp *interp, int flags)",
+    "void Tcl_PanicProc(const char *format, ...)",
+    "int Tcl_ResolveCmdProc(Tcl_Interp *interp, const char *name, Tcl_Namespace *context, \
+     int flags, Tcl_Command *rPtr)",
+    "int Tcl_ResolveCompiledVarProc(Tcl_Interp *interp, const char *name, int length, \
+     Tcl_Namespace *context, Tcl_ResolvedVarInfo **rPtr)",
+    "int Tcl_ResolveVarProc(Tcl_Interp *interp, const char *name, Tcl_Namespace *context, \
+     int flags, Tcl_Var *rPtr)",
+    "void Tcl_ScaleTimeProc(Tcl_Time *timebuf, ClientData clientData)",
+    "int Tcl_SetFromAnyProc(Tcl_Interp *interp, Tcl_Obj *objPtr)",
+    "void Tcl_TcpAcceptProc(ClientData callbackData, Tcl_Channel chan, char *address, \
+     int port)",
+    "void Tcl_ThreadCreateProc(ClientData clientData)",
+    "void Tcl_TimerProc(ClientData clientData)",
+    "void Tcl_UpdateStringProc(Tcl_Obj *objPtr)",
+    "char *Tcl_VarTraceProc(ClientData clientData, Tcl_Interp *interp, const char *part1, \
+     const char *part2, int flags)",
+    "int CompileHookProc(Tcl_Interp *interp, struct CompileEnv *compEnvPtr, \
+     ClientData clientData)",
+    "int CompileProc(Tcl_Interp *interp, Tcl_Parse *parsePtr, struct Command *cmdPtr, \
+     struct CompileEnv *compEnvPtr)",
+    "void ProcErrorProc(Tcl_Interp *interp, Tcl_Obj *procNameObj)",
+    "int TclGetFileAttrProc(Tcl_Interp *interp, int objIndex, Tcl_Obj *fileName, \
+     Tcl_Obj **attrObjPtrPtr)",
+    "void TclInitProcessGlobalValueProc(char **valuePtr, int *lengthPtr, \
+     Tcl_Encoding *encodingPtr)",
+    "int TclSetFileAttrProc(Tcl_Interp *interp, int objIndex, Tcl_Obj *fileName, \
+     Tcl_Obj *attrObjPtr)",
+    "int TclStatProc_(const char *path, struct stat *buf)",
+    "int TclAccessProc_(const char *path, int mode)",
+    "Tcl_Channel TclOpenFileChannelProc_(Tcl_Interp *interp, const char *fileName, \
+     const char *modeString, int permissions)",
+];
+
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
+    "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl",
+    "in", "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "static", "struct", "super", "trait", "true", "try", "type", "typeof", "unsafe",
+    "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+/// A C type: a base type and any levels of pointers to it.
+#[derive(Clone, Debug)]
+struct CType {
+    base: String,
+    base_const: bool,
+    /// Whether each level of pointer (innermost first) is itself `const`.
+    pointers: Vec<bool>,
+}
+
+/// A C function declaration (or function type).
+#[derive(Debug)]
+struct CFunction {
+    name: String,
+    noreturn: bool,
+    ret: CType,
+    params: Vec<(Option<String>, CType)>,
+    variadic: bool,
+}
+
+/// A stubs interface: a named table and the declarations in its slots.
+#[derive(Debug, Default)]
+struct Interface {
+    name: String,
+    hooks: Vec<String>,
+    /// Declarations by slot and then by platform (`generic`, `unix`, ...).
+    slots: BTreeMap<usize, BTreeMap<String, String>>,
+}
+
+/// Turns `.decls` files into Rust declarations of the stubs tables.
+#[derive(Debug)]
+pub struct Generator {
+    major: u32,
+    platform: Platform,
+    renames: BTreeMap<String, String>,
+    interfaces: Vec<Interface>,
+}
+
+/// The types and imports referenced by generated declarations.
+#[derive(Default)]
+struct Used {
+    ffi: BTreeSet<&'static str>,
+    handles: BTreeSet<String>,
+    opaques: BTreeSet<String>,
+    callbacks: BTreeSet<String>,
+}
+
+impl Generator {
+    /// Creates a generator for a Tcl major version and platform.
+    pub fn new(major: u32, platform: Platform) -> Generator {
+        Generator {
+            major,
+            platform,
+            renames: BTreeMap::new(),
+            interfaces: Vec::new(),
+        }
+    }
+
+    /// Names the field for the C function `from` as `to` instead.
+    pub fn rename(&mut self, from: &str, to: &str) {
+        self.renames.insert(from.to_string(), to.to_string());
+    }
+
+    /// Reads the interfaces declared in a `.decls` script.
+    pub fn parse(&mut self, script: &str) -> Result<(), String> {
+        let mut current: Option<usize> = None;
+        for words in tcl_commands(script)? {
+            match (words[0].as_str(), words.len()) {
+                ("library", 2) | ("scspec", 2) | ("export", 2) | ("epoch", 2) => {}
+                ("interface", 2) => {
+                    let name = &words[1];
+                    let index = match self.interfaces.iter().position(|i| &i.name == name) {
+                        Some(index) => index,
+                        None => {
+                            self.interfaces.push(Interface {
+                                name: name.clone(),
+                                ..Default::default()
+                            });
+                            self.interfaces.len() - 1
+                        }
+                    };
+                    current = Some(index);
+                }
+                ("hooks", 2) => {
+                    let interface = self.current(current, "hooks")?;
+                    interface.hooks = words[1].split_whitespace().map(String::from).collect();
+                }
+                ("declare", 3) | ("declare", 4) => {
+                    let slot: usize = words[1]
+                        .parse()
+                        .map_err(|_| format!("bad slot number \"{}\"", words[1]))?;
+                    let platforms = if words.len() == 4 {
+                        platforms(&words[2])?
+                    } else {
+                        vec!["generic".to_string()]
+                    };
+                    let decl = words.last().unwrap().split_whitespace().collect::<Vec<_>>();
+                    let interface = self.current(current, "declare")?;
+                    for platform in platforms {
+                        interface
+                            .slots
+                            .entry(slot)
+                            .or_default()
+                            .insert(platform, decl.join(" "));
+                    }
+                }
+                _ => return Err(format!("unexpected command \"{}\"", words.join(" "))),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn current(&mut self, current: Option<usize>, command: &str) -> Result<&mut Interface, String> {
+        match current {
+            Some(index) => Ok(&mut self.interfaces[index]),
+            None => Err(format!("\"{}\" outside of an interface", command)),
+        }
+    }
+
+    /// Generates the Rust declarations of all interfaces read so far.
+    pub fn generate(&self) -> Result<String, String> {
+        let mut used = Used::default();
+        let mut tables = String::new();
+        let mut layouts = String::new();
+
+        for interface in &self.interfaces {
+            let struct_name = format!("{}Stubs", capitalize(&interface.name));
+            let mut layout = vec![("magic".to_string(), "magic".to_string())];
+            layout.push(("hooks".to_string(), "hooks".to_string()));
+
+            let hooks_type = if interface.hooks.is_empty() {
+                used.ffi.insert("c_void");
+                "*mut c_void".to_string()
+            } else {
+                let hooks_name = format!("{}StubHooks", capitalize(&interface.name));
+                // The fields are named after the C struct's.
+                writeln!(
+                    tables,
+                    "#[repr(C)]\n#[derive(Debug)]\n#[allow(non_snake_case)]\npub(super) struct {} {{",
+                    hooks_name
+                )
+                .unwrap();
+                for hook in &interface.hooks {
+                    let hooked = if self.interfaces.iter().any(|i| &i.name == hook) {
+                        format!("*const {}Stubs", capitalize(hook))
+                    } else {
+                        used.ffi.insert("c_void");
+                        "*const c_void".to_string()
+                    };
+                    writeln!(tables, "    pub(super) {}Stubs: {},", hook, hooked).unwrap();
+                }
+                tables.push_str("}\n\n");
+                format!("*const {}", hooks_name)
+            };
+
+            // The fields are named after the C functions.
+            writeln!(
+                tables,
+                "#[repr(C)]\n#[derive(Debug)]\n#[allow(non_snake_case)]\npub(super) struct {} {{",
+                struct_name
+            )
+            .unwrap();
+            tables.push_str("    pub(super) magic: u32,\n");
+            writeln!(tables, "    pub(super) hooks: {},", hooks_type).unwrap();
+
+            let has_unix = interface
+                .slots
+                .values()
+                .any(|decls| decls.contains_key("unix"));
+            let mut entries = Vec::new();
+            for (&slot, decls) in &interface.slots {
+                if let Some(decl) = self.select(decls, has_unix) {
+                    entries.push((slot, decl));
+                }
+            }
+            let mut next = 0;
+            for (slot, decl) in entries {
+                while next < slot {
+                    used.ffi.insert("c_void");
+                    writeln!(
+                        tables,
+                        "    pub(super) _reserved_{}: *const c_void, // {}",
+                        next, next
+                    )
+                    .unwrap();
+                    next += 1;
+                }
+                let function = parse_function(decl)?;
+                let field = self.renames.get(&function.name).unwrap_or(&function.name);
+                let signature = self
+                    .fn_type(&function, &mut used)
+                    .map_err(|err| format!("{} in \"{}\"", err, decl))?;
+                writeln!(
+                    tables,
+                    "    pub(super) {}: {}, // {}",
+                    field, signature, slot
+                )
+                .unwrap();
+                layout.push((function.name.clone(), field.clone()));
+                next = slot + 1;
+            }
+            tables.push_str("}\n\n");
+
+            writeln!(
+                layouts,
+                "    (\n        \"{}\",\n        std::mem::size_of::<{}>(),\n        &[",
+                struct_name, struct_name
+            )
+            .unwrap();
+            for (name, field) in layout {
+                writeln!(
+                    layouts,
+                    "            (\"{}\", std::mem::offset_of!({}, {})),",
+                    name, struct_name, field
+                )
+                .unwrap();
+            }
+            layouts.push_str("        ],\n    ),\n");
+        }
+
+        // Callbacks may reference further types, including other callbacks.
+        let mut callbacks = BTreeMap::new();
+        loop {
+            let pending: Vec<String> = used
+                .callbacks
+                .iter()
+                .filter(|name| !callbacks.contains_key(*name))
+                .cloned()
+                .collect();
+            if pending.is_empty() {
+                break;
+            }
+            for name in pending {
+                let prototype = CALLBACKS
+                    .iter()
+                    .find(|prototype| parse_function(prototype).unwrap().name == name)
+                    .unwrap();
+                let function = parse_function(prototype)?;
+                let signature = self
+                    .fn_type(&function, &mut used)
+                    .map_err(|err| format!("{} in \"{}\"", err, prototype))?;
+                callbacks.insert(name, signature);
+            }
+        }
+
+        let mut out = String::new();
+        out.push_str("// Generated by build.rs from the Tcl stubs declarations; do not edit.\n\n");
+        for ffi in &used.ffi {
+            writeln!(out, "use std::ffi::{};", ffi).unwrap();
+        }
+        out.push('\n');
+        for handle in &used.handles {
+            let struct_name = format!("{}_", handle);
+            writeln!(
+                out,
+                "#[repr(C)]\n{}pub(super) struct {} {{\n    _private: [u8; 0],\n}}\n\
+                 {}pub(super) type {} = *mut {};\n",
+                allow_c_name(&struct_name),
+                struct_name,
+                allow_c_name(handle),
+                handle,
+                struct_name
+            )
+            .unwrap();
+        }
+        for opaque in &used.opaques {
+            writeln!(
+                out,
+                "#[repr(C)]\n{}pub(super) struct {} {{\n    _private: [u8; 0],\n}}\n",
+                allow_c_name(opaque),
+                opaque
+            )
+            .unwrap();
+        }
+        for (name, signature) in &callbacks {
+            // The parameters are named after the C declarations.
+            writeln!(
+                out,
+                "{}#[allow(non_snake_case)]\npub(super) type {} = {};\n",
+                allow_c_name(name),
+                name,
+                signature
+            )
+            .unwrap();
+        }
+        out.push_str(&tables);
+        out.push_str(
+            "/// The offset of a stubs table entry, by the name of the C function in it.\n\
+             pub(super) type StubOffset = (&'static str, usize);\n\n\
+             /// The name, size and entries of a stubs table.\n\
+             pub(super) type StubLayout = (&'static str, usize, &'static [StubOffset]);\n\n\
+             /// The size of each stubs table and the offset of each of its entries, by the\n\
+             /// name of the C function in the entry.\n\
+             pub(super) const STUB_LAYOUTS: &[StubLayout] = &[\n",
+        );
+        out.push_str(&layouts);
+        out.push_str("];\n");
+
+        Ok(out)
+    }
+
+    /// Picks the declaration filling a slot on the target platform.
+    ///
+    /// As in `genStubs.tcl`, the `unix` table also holds the `macosx` entries
+    /// of interfaces that have `unix` entries.
+    fn select<'a>(
+        &self,
+        decls: &'a BTreeMap<String, String>,
+        has_unix: bool,
+    ) -> Option<&'a String> {
+        decls.get("generic").or_else(|| match self.platform {
+            Platform::Unix => decls
+                .get("unix")
+                .or_else(|| decls.get("macosx").filter(|_| has_unix)),
+            Platform::MacOsX => decls.get("macosx").or_else(|| decls.get("unix")),
+            Platform::Win => decls.get("win"),
+        })
+    }
+
+    fn fn_type(&self, function: &CFunction, used: &mut Used) -> Result<String, String> {
+        let mut params = Vec::new();
+        for (name, ty) in &function.params {
+            let ty = self.rust_type(ty, used)?;
+            params.push(match name {
+                Some(name) if KEYWORDS.contains(&name.as_str()) => format!("r#{}: {}", name, ty),
+                Some(name) => format!("{}: {}", name, ty),
+                None => ty,
+            });
+        }
+        if function.variadic {
+            params.push("...".to_string());
+        }
+
+        let mut signature = format!("extern \"C\" fn({})", params.join(", "));
+        if function.noreturn {
+            signature.push_str(" -> !");
+        } else {
+            let ret = self.rust_type(&function.ret, used)?;
+            if ret != "()" {
+                signature.push_str(" -> ");
+                signature.push_str(&ret);
+            }
+        }
+        Ok(signature)
+    }
+
+    fn rust_type(&self, ty: &CType, used: &mut Used) -> Result<String, String> {
+        if let Some(alias) = self.alias(&ty.base) {
+            let mut alias = parse_type(&tokenize(alias)?)?;
+            alias.base_const |= ty.base_const;
+            alias.pointers.extend(&ty.pointers);
+            return self.rust_type(&alias, used);
+        }
+
+        let base = ty.base.as_str();
+        // The number of pointer levels the base type already accounts for.
+        let mut consumed = 0;
+        let mut rust = if base == "void" {
+            if ty.pointers.is_empty() {
+                return Ok("()".to_string());
+            }
+            used.ffi.insert("c_void");
+            "c_void".to_string()
+        } else if base == "Tcl_Interp" && !ty.pointers.is_empty() {
+            consumed = 1;
+            "*const Interpreter".to_string()
+        } else if base == "Tcl_Obj" {
+            "RawObject".to_string()
+        } else if base == "Tcl_ObjType" {
+            "ObjectType".to_string()
+        } else if let Some((_, rust)) = SCALARS.iter().find(|(c, _)| *c == base) {
+            if rust.starts_with("c_") {
+                used.ffi.insert(rust);
+            }
+            rust.to_string()
+        } else if HANDLES.contains(&base) {
+            used.handles.insert(base.to_string());
+            base.to_string()
+        } else if OPAQUES.contains(&base) && !ty.pointers.is_empty() {
+            used.opaques.insert(base.to_string());
+            base.to_string()
+        } else if self.is_callback(base) && !ty.pointers.is_empty() {
+            used.callbacks.insert(base.to_string());
+            consumed = 1;
+            format!("Option<{}>", base)
+        } else {
+            return Err(format!("unknown type \"{}\"", base));
+        };
+
+        for level in consumed..ty.pointers.len() {
+            let pointee_const = if level == 0 {
+                ty.base_const
+            } else {
+                ty.pointers[level - 1]
+            };
+            let kind = if pointee_const { "const" } else { "mut" };
+            rust = format!("*{} {}", kind, rust);
+        }
+        Ok(rust)
+    }
+
+    fn alias(&self, base: &str) -> Option<&'static str> {
+        if base == "Tcl_UniChar" {
+            return Some(if self.major < 9 {
+                "unsigned short"
+            } else {
+                "int"
+            });
+        }
+        ALIASES
+            .iter()
+            .find(|(c, _)| *c == base)
+            .map(|(_, alias)| *alias)
+    }
+
+    fn is_callback(&self, base: &str) -> bool {
+        CALLBACKS
+            .iter()
+            .any(|prototype| parse_function(prototype).is_ok_and(|f| f.name == base))
+    }
+}
+
+/// Allows the lints a C type name (like `Tcl_Channel` or `FILE`) trips over
+/// when used as a Rust type name.
+fn allow_c_name(name: &str) -> &'static str {
+    if name.len() > 1 && !name.bytes().any(|b| b.is_ascii_lowercase()) {
+        "#[allow(clippy::upper_case_acronyms)]\n"
+    } else if name.contains('_') {
+        "#[allow(non_camel_case_types)]\n"
+    } else {
+        ""
+    }
+}
+
+/// Reads the platforms a declaration applies to.
+fn platforms(word: &str) -> Result<Vec<String>, String> {
+    let words: Vec<&str> = word.split_whitespace().collect();
+    match words.first() {
+        // Deprecated functions keep their slots.
+        Some(&"deprecated") => Ok(vec!["generic".to_string()]),
+        // Functions without a stub entry leave their slots reserved.
+        Some(&"nostub") => Ok(Vec::new()),
+        _ => words
+            .iter()
+            .map(|platform| match *platform {
+                "generic" | "unix" | "win" | "macosx" => Ok(platform.to_string()),
+                _ => Err(format!("unknown platform \"{}\"", platform)),
+            })
+            .collect(),
+    }
+}
+
+fn capitalize(name: &str) -> String {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Splits a Tcl script into commands of words.
+///
+/// This handles only the subset of Tcl `.decls` files use: comments, bare
+/// words, and braced or quoted words without substitutions.
+fn tcl_commands(script: &str) -> Result<Vec<Vec<String>>, String> {
+    let chars: Vec<char> = script.chars().collect();
+    let mut commands = Vec::new();
+    let mut words: Vec<String> = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '\n' || c == ';' {
+            if !words.is_empty() {
+                commands.push(std::mem::take(&mut words));
+            }
+            i += 1;
+        } else if c.is_whitespace() {
+            i += 1;
+        } else if c == '#' && words.is_empty() {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+        } else if c == '{' {
+            let start = i + 1;
+            let mut depth = 0;
+            loop {
+                match chars.get(i) {
+                    Some('{') => depth += 1,
+                    Some('}') => {
+                        depth -= 1;
+                        if depth == 0 {
+                            break;
+                        }
+                    }
+                    Some(_) => {}
+                    None => return Err("missing close-brace".to_string()),
+                }
+                i += 1;
+            }
+            words.push(chars[start..i].iter().collect());
+            i += 1;
+        } else if c == '"' {
+            let start = i + 1;
+            i += 1;
+            while i < chars.len() && chars[i] != '"' {
+                i += 1;
+            }
+            if i == chars.len() {
+                return Err("missing \"".to_string());
+            }
+            words.push(chars[start..i].iter().collect());
+            i += 1;
+        } else {
+            let start = i;
+            while i < chars.len() && !chars[i].is_whitespace() && chars[i] != ';' {
+                i += 1;
+            }
+            words.push(chars[start..i].iter().collect());
+        }
+    }
+    if !words.is_empty() {
+        commands.push(words);
+    }
+
+    Ok(commands)
+}
+
+/// Splits a C declaration into identifiers and punctuation.
+fn tokenize(decl: &str) -> Result<Vec<String>, String> {
+    let mut tokens = Vec::new();
+    let chars: Vec<char> = decl.chars().collect();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c.is_ascii_alphanumeric() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            let token: String = chars[start..i].iter().collect();
+            match token.as_str() {
+                // Tcl 8.6 headers define these as `const` by default.
+                "CONST" | "CONST84" | "CONST84_RETURN" | "CONST86" => {
+                    tokens.push("const".to_string())
+                }
+                "struct" | "EXTERN" | "TCL_NORETURN1" => {}
+                _ => tokens.push(token),
+            }
+        } else if chars[i..].starts_with(&['.', '.', '.']) {
+            tokens.push("...".to_string());
+            i += 3;
+        } else if "*(),[]".contains(c) {
+            tokens.push(c.to_string());
+            i += 1;
+        } else {
+            return Err(format!("unexpected \"{}\" in \"{}\"", c, decl));
+        }
+    }
+
+    Ok(tokens)
+}
+
+fn parse_function(decl: &str) -> Result<CFunction, String> {
+    let mut tokens = tokenize(decl)?;
+    let noreturn = tokens.first().is_some_and(|token| token == "TCL_NORETURN");
+    if noreturn {
+        tokens.remove(0);
+    }
+
+    let open = tokens
+        .iter()
+        .position(|token| token == "(")
+        .filter(|&open| open > 1 && tokens.last().is_some_and(|token| token == ")"))
+        .ok_or_else(|| format!("malformed declaration \"{}\"", decl))?;
+    let name = tokens[open - 1].clone();
+    let ret = parse_type(&tokens[..open - 1])?;
+
+    let mut params = Vec::new();
+    let mut variadic = false;
+    let inner = &tokens[open + 1..tokens.len() - 1];
+    if inner != ["void"] {
+        for param in inner.split(|token| token == ",") {
+            if param == ["..."] {
+                variadic = true;
+            } else {
+                params.push(parse_param(param).map_err(|err| format!("{} in \"{}\"", err, decl))?);
+            }
+        }
+    }
+
+    Ok(CFunction {
+        name,
+        noreturn,
+        ret,
+        params,
+        variadic,
+    })
+}
+
+fn parse_param(tokens: &[String]) -> Result<(Option<String>, CType), String> {
+    let mut tokens = tokens.to_vec();
+    let array = tokens.ends_with(&["[".to_string(), "]".to_string()]);
+    if array {
+        tokens.truncate(tokens.len() - 2);
+    }
+
+    // The last identifier names the parameter unless it is part of the type.
+    let type_words = tokens
+        .iter()
+        .filter(|token| *token != "const" && *token != "*")
+        .count();
+    let name = match tokens.last() {
+        Some(last)
+            if type_words > 1
+                && last != "*"
+                && !matches!(
+                    last.as_str(),
+                    "char" | "short" | "int" | "long" | "float" | "double" | "unsigned"
+                ) =>
+        {
+            tokens.pop()
+        }
+        _ => None,
+    };
+
+    let mut ty = parse_type(&tokens)?;
+    if array {
+        ty.pointers.push(false);
+    }
+    Ok((name, ty))
+}
+
+fn parse_type(tokens: &[String]) -> Result<CType, String> {
+    let mut words = Vec::new();
+    let mut base_const = false;
+    let mut pointers: Vec<bool> = Vec::new();
+
+    for token in tokens {
+        match token.as_str() {
+            "const" => match pointers.last_mut() {
+                Some(pointer) => *pointer = true,
+                None => base_const = true,
+            },
+            "*" => pointers.push(false),
+            "signed" | "unsigned" | "char" | "short" | "int" | "long" | "float" | "double"
+                if pointers.is_empty() =>
+            {
+                words.push(token.as_str())
+            }
+            _ if pointers.is_empty() && words.is_empty() => words.push(token.as_str()),
+            _ => return Err(format!("unexpected \"{}\" in type", token)),
+        }
+    }
+    if words.is_empty() {
+        return Err("missing type".to_string());
+    }
+
+    // `long int` and friends name the same types as `long`.
+    if words.len() > 1 && words.last() == Some(&"int") {
+        words.pop();
+        if words == ["unsigned"] {
+            words.push("int");
+        }
+    }
+    Ok(CType {
+        base: words.join(" "),
+        base_const,
+        pointers,
+    })
+}
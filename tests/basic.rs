@@ -14,13 +14,15 @@ fn test_init(interp: &Interpreter) -> Result<TclStatus, String> {
         Ok(TclStatus::Ok)
     }
 
-    interp.create_command("mycmd", cmd)
+    interp.create_command("mycmd", cmd)?;
+    Ok(TclStatus::Ok)
 }
 
 #[test]
 fn interpreter() -> Result<(), String> {
     let test_interp = TestInterpreter::new();
-    test_interp.as_ref().init_global_functions();
+    test_interp.as_ref().init_global_functions().unwrap();
+    let expected = if cfg!(feature = "tcl86") { "8.6" } else { "9.0" };
 
     assert_eq!(
         test_interp
@@ -29,7 +31,10 @@ fn interpreter() -> Result<(), String> {
         .expect("interp failed to return result")
         .get_string()
         .to_string()
-        , "9.0");
+        , expected);
+
+    let (major, minor, _) = test_interp.as_ref().tcl_version();
+    assert_eq!(format!("{}.{}", major, minor), expected);
     Ok(())
 }
 
@@ -59,6 +64,56 @@ fn eval() -> Result<(), String> {
     Ok(())
 }
 
+#[test]
+fn eval_obj() -> Result<(), String> {
+    let test_interp = TestInterpreter::new();
+    assert_eq!(RteaTest_Init(test_interp.as_ptr()), TclStatus::Ok);
+    let interp = test_interp.as_ref();
+
+    let script = Object::new_string("incr counter");
+    for i in 1..=3 {
+        let result = interp.eval_obj(&script, EvalFlags::NONE)?;
+        assert_eq!(result.get_string(), i.to_string());
+    }
+
+    fn global_counter(interp: &Interpreter, _args: Vec<Object>) -> Result<TclStatus, Object> {
+        let result = interp.eval_obj(&Object::new_string("set counter"), EvalFlags::GLOBAL)?;
+        interp.set_obj_result(&result);
+        Ok(TclStatus::Ok)
+    }
+
+    interp.create_obj_command("global_counter", global_counter)?;
+    let result = interp
+        .eval("proc q {} { set counter local; global_counter }; q")
+        .map_err(|obj| obj.get_string().to_string())?;
+    assert_eq!(result.get_string(), "3");
+
+    Ok(())
+}
+
+#[test]
+fn eval_objv() -> Result<(), String> {
+    let test_interp = TestInterpreter::new();
+    assert_eq!(RteaTest_Init(test_interp.as_ptr()), TclStatus::Ok);
+    let interp = test_interp.as_ref();
+
+    let objv = vec![
+        Object::new_string("set"),
+        Object::new_string("value"),
+        Object::new_string("has [brackets] and $dollars"),
+    ];
+    let result = interp.eval_objv(&objv, EvalFlags::NONE)?;
+    assert_eq!(result.get_string(), "has [brackets] and $dollars");
+
+    let err = interp
+        .eval_objv(&[Object::new_string("error"), Object::new_string("boom")], EvalFlags::NONE)
+        .expect_err("error should propagate");
+    assert_eq!(err.message().get_string(), "boom");
+    assert!(err.error_info().is_some());
+
+    Ok(())
+}
+
 #[test]
 fn create_command() -> Result<(), String> {
     let test_interp = TestInterpreter::new();
@@ -182,3 +237,95 @@ fn obj_wrapper() -> Result<(), String> {
 
     Ok(())
 }
+
+#[test]
+fn init_global_functions_per_thread() {
+    let test_interp = TestInterpreter::new();
+    test_interp.as_ref().init_global_functions().unwrap();
+    assert!(rtea::is_initialized());
+
+    let threads: Vec<_> = (0..4)
+        .map(|i| {
+            std::thread::spawn(move || {
+                let test_interp = TestInterpreter::new();
+                let interp = test_interp.as_ref();
+                interp.init_global_functions().unwrap();
+                let result = interp.eval(&format!("expr {{{} * 2}}", i)).unwrap();
+                assert_eq!(result.get_string(), (i * 2).to_string());
+            })
+        })
+        .collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+
+    test_interp.as_ref().init_global_functions().unwrap();
+}
+
+#[repr(C)]
+struct MathApi {
+    square: extern "C" fn(i64) -> i64,
+}
+
+extern "C" fn square(x: i64) -> i64 {
+    x * x
+}
+
+static MATH_API: MathApi = MathApi { square };
+
+#[test]
+fn packages() -> Result<(), String> {
+    let test_interp = TestInterpreter::new();
+    test_interp.as_ref().init_global_functions().unwrap();
+    let interp = test_interp.as_ref();
+
+    assert!(interp.package_present("mathapi", None, false).is_err());
+    interp.provide_package_data("mathapi", "1.2.0", &MATH_API)?;
+    interp.provide_package("plain", "2.0")?;
+
+    assert_eq!(interp.package_present("mathapi", None, false)?, "1.2.0");
+    assert_eq!(interp.require_package("mathapi", Some("1.1"), false)?, "1.2.0");
+    assert!(interp.require_package("mathapi", Some("1.1"), true).is_err());
+    assert!(interp.require_package("mathapi", Some("2"), false).is_err());
+
+    let (version, api) = unsafe { interp.require_package_data::<MathApi>("mathapi", None, false)? };
+    assert_eq!(version, "1.2.0");
+    assert_eq!((api.square)(7), 49);
+    assert!(unsafe { interp.require_package_data::<MathApi>("plain", None, false) }.is_err());
+
+    Ok(())
+}
+
+#[test]
+#[cfg(target_pointer_width = "64")]
+fn wrong_tcl_version() {
+    #[repr(C)]
+    struct FakeInterp {
+        result: *const u8,
+        free_proc: *const u8,
+        error_line: isize,
+        stubs: *const u32,
+    }
+
+    // The magic number of the stubs table for the version rtea was not
+    // built for (on 64-bit platforms).
+    let (magic, feature): (u32, &str) = if cfg!(feature = "tcl86") {
+        (0xFCA3BACB + 8, "tcl90")
+    } else {
+        (0xFCA3BACF, "tcl86")
+    };
+    let fake = FakeInterp {
+        result: std::ptr::null(),
+        free_proc: std::ptr::null(),
+        error_line: 0,
+        stubs: &magic,
+    };
+
+    match Interpreter::from_raw(&fake as *const FakeInterp as *const Interpreter) {
+        Err(err @ Error::InvalidStubs(_)) => {
+            let message = err.to_string();
+            assert!(message.contains(feature), "{}", message);
+        }
+        other => panic!("expected InvalidStubs, got {:?}", other),
+    }
+}
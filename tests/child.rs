@@ -0,0 +1,78 @@
+use rtea::*;
+
+mod common;
+
+use common::TestInterpreter;
+
+#[test]
+fn safe_child() -> Result<(), String> {
+    let test_interp = TestInterpreter::new();
+    test_interp.as_ref().init_global_functions().unwrap();
+    let interp = test_interp.as_ref();
+
+    fn host_log(interp: &Interpreter, args: Vec<&str>) -> Result<TclStatus, String> {
+        interp.set_result(&args[1..].join(" "));
+        Ok(TclStatus::Ok)
+    }
+    interp.create_command("host_log", host_log)?;
+
+    let handle = interp.create_child("sandbox", true)?;
+    let child = handle.get().ok_or("child vanished")?;
+    assert!(child.is_safe());
+    assert!(!interp.is_safe());
+    assert_eq!(
+        child
+            .get_parent()
+            .map(|parent| parent as *const Interpreter),
+        Some(interp as *const Interpreter)
+    );
+    assert_eq!(
+        interp.get_child("sandbox").map(|child| child.as_ptr()),
+        Some(child as *const Interpreter)
+    );
+    assert!(interp.get_child("missing").is_none());
+    assert!(interp.get_parent().is_none());
+
+    child.create_alias("log", interp, "host_log", &[Object::new_string("[plugin]")])?;
+    let result = child
+        .eval("log hello")
+        .map_err(|obj| obj.get_string().to_string())?;
+    assert_eq!(result.get_string(), "[plugin] hello");
+
+    let alias = child.get_alias("log")?;
+    assert_eq!(alias.target.as_ptr(), interp as *const Interpreter);
+    assert_eq!(alias.target_command, "host_log");
+    assert_eq!(alias.prefix.len(), 1);
+    assert!(child.get_alias("set").is_err());
+
+    child.hide_command("log", Some("hidden_log"))?;
+    assert!(child.eval("log hello").is_err());
+    let result = interp
+        .eval("interp invokehidden sandbox hidden_log again")
+        .map_err(|obj| obj.get_string().to_string())?;
+    assert_eq!(result.get_string(), "[plugin] again");
+    child.expose_command("hidden_log", Some("log"))?;
+    child
+        .eval("log exposed")
+        .map_err(|obj| obj.get_string().to_string())?;
+
+    child
+        .eval("proc run {} { return ran }")
+        .map_err(|obj| obj.get_string().to_string())?;
+    child.hide_command("::run", None)?;
+    let result = interp
+        .eval("interp invokehidden sandbox run")
+        .map_err(|obj| obj.get_string().to_string())?;
+    assert_eq!(result.get_string(), "ran");
+
+    assert!(interp.create_child("sandbox", false).is_err());
+
+    interp
+        .eval("interp delete sandbox")
+        .map_err(|obj| obj.get_string().to_string())?;
+    assert!(handle.get().is_none());
+    assert!(alias.target.get().is_some());
+    assert!(interp.get_child("sandbox").is_none());
+
+    Ok(())
+}
@@ -1,6 +1,7 @@
 use rtea::*;
 
-#[link(name = "tcl9.0")]
+#[cfg_attr(feature = "tcl86", link(name = "tcl8.6"))]
+#[cfg_attr(feature = "tcl90", link(name = "tcl9.0"))]
 unsafe extern "C" {
     // fn Tcl_FindExecutable(name: *const c_char);
     fn Tcl_CreateInterp() -> *mut Interpreter;
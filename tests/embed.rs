@@ -0,0 +1,37 @@
+#![cfg(feature = "embed")]
+
+use rtea::*;
+
+#[test]
+fn owned_interpreter() -> Result<(), String> {
+    let interp = OwnedInterpreter::without_init().map_err(|err| format!("{:?}", err))?;
+    let result = interp
+        .eval("set x [expr {6 * 7}]")
+        .map_err(|obj| obj.get_string().to_string())?;
+    assert_eq!(result.get_string(), "42");
+
+    let other = OwnedInterpreter::without_init().map_err(|err| format!("{:?}", err))?;
+    assert!(other.eval("set x").is_err());
+    drop(other);
+
+    let value = interp
+        .get_var(&Object::new_string("x"))
+        .map_err(|obj| obj.get_string().to_string())?;
+    assert_eq!(value.get_string(), "42");
+
+    Ok(())
+}
+
+#[test]
+fn initialized_interpreter() -> Result<(), String> {
+    let interp = OwnedInterpreter::new().map_err(|err| err.to_string())?;
+    interp
+        .eval("package require Tcl")
+        .map_err(|obj| obj.get_string().to_string())?;
+    let library = interp
+        .eval("info procs auto_load")
+        .map_err(|obj| obj.get_string().to_string())?;
+    assert_eq!(library.get_string(), "auto_load");
+
+    Ok(())
+}
@@ -0,0 +1,139 @@
+use rtea::*;
+
+mod common;
+
+use common::TestInterpreter;
+
+#[test]
+fn expr() -> Result<(), String> {
+    let test_interp = TestInterpreter::new();
+    test_interp.as_ref().init_global_functions().unwrap();
+    let interp = test_interp.as_ref();
+
+    let rule = Object::new_string("$load > 0.5 && $user ne {root}");
+    interp
+        .eval("set load 0.75; set user alice")
+        .map_err(|obj| obj.get_string().to_string())?;
+    assert!(interp.expr_bool(&rule)?);
+    interp
+        .eval("set user root")
+        .map_err(|obj| obj.get_string().to_string())?;
+    assert!(!interp.expr_bool(&rule)?);
+
+    assert_eq!(interp.expr_i64(&Object::new_string("1 << 40"))?, 1 << 40);
+    assert_eq!(interp.expr_f64(&Object::new_string("1 / 4.0"))?, 0.25);
+    assert_eq!(
+        interp
+            .expr_obj(&Object::new_string("[string toupper abc]"))?
+            .get_string(),
+        "ABC"
+    );
+
+    let err = interp
+        .expr_i64(&Object::new_string("1 / 0"))
+        .expect_err("division by zero should fail");
+    assert_eq!(err.message().get_string(), "divide by zero");
+    assert_eq!(
+        err.error_code().map(|code| code.get_string()),
+        Some("ARITH DIVZERO {divide by zero}")
+    );
+
+    assert!(interp.expr_i64(&Object::new_string("1.5")).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn eval_file() -> Result<(), String> {
+    let test_interp = TestInterpreter::new();
+    test_interp.as_ref().init_global_functions().unwrap();
+    let interp = test_interp.as_ref();
+
+    let dir = std::env::temp_dir().join(format!("rtea-eval-file-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+
+    let good = dir.join("good.tcl");
+    std::fs::write(&good, "set loaded [info script]\nexpr {6 * 7}\n").map_err(|e| e.to_string())?;
+    let result = interp.eval_file(&good, Some("utf-8"))?;
+    assert_eq!(result.get_string(), "42");
+    let loaded = interp
+        .eval("set loaded")
+        .map_err(|obj| obj.get_string().to_string())?;
+    assert_eq!(loaded.get_string(), good.to_str().unwrap());
+
+    let bad = dir.join("bad.tcl");
+    std::fs::write(&bad, "set x 1\n\nerror oops\n").map_err(|e| e.to_string())?;
+    let err = interp
+        .eval_file(&bad, None)
+        .expect_err("script should fail");
+    assert_eq!(err.message().get_string(), "oops");
+    assert_eq!(err.error_line(), Some(3));
+    assert!(
+        err.error_info()
+            .map(|info| info.get_string().contains("bad.tcl"))
+            .unwrap_or(false)
+    );
+
+    assert!(interp.eval_file(dir.join("missing.tcl"), None).is_err());
+
+    std::fs::remove_dir_all(&dir).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[test]
+fn subst() -> Result<(), String> {
+    let test_interp = TestInterpreter::new();
+    test_interp.as_ref().init_global_functions().unwrap();
+    let interp = test_interp.as_ref();
+
+    interp
+        .eval("set name world")
+        .map_err(|obj| obj.get_string().to_string())?;
+    let template = Object::new_string("hello $name [string length $name]\\t!");
+
+    let result = interp.subst(&template, SubstFlags::ALL)?;
+    assert_eq!(result.get_string(), "hello world 5\t!");
+
+    let result = interp.subst(&template, SubstFlags::VARIABLES)?;
+    assert_eq!(result.get_string(), "hello world [string length world]\\t!");
+
+    let result = interp.subst(&template, SubstFlags::COMMANDS | SubstFlags::BACKSLASHES)?;
+    assert_eq!(result.get_string(), "hello $name 5\t!");
+
+    let err = interp
+        .subst(&Object::new_string("[error boom]"), SubstFlags::ALL)
+        .expect_err("command error should propagate");
+    assert_eq!(err.message().get_string(), "boom");
+
+    Ok(())
+}
+
+#[test]
+fn script_builder() -> Result<(), String> {
+    let test_interp = TestInterpreter::new();
+    test_interp.as_ref().init_global_functions().unwrap();
+    let interp = test_interp.as_ref();
+
+    let hostile = "a b; [set pwned 1] $x {";
+    let command = tcl_script!("set", "value", hostile);
+    let result = interp.eval_objv(command.words(), EvalFlags::NONE)?;
+    assert_eq!(result.get_string(), hostile);
+    assert!(interp.eval("set pwned").is_err());
+
+    let script = tcl_script!("#cmd", "#arg", "", 42, 1.5).to_string();
+    assert_eq!(script, "{#cmd} #arg {} 42 1.5");
+    let result = interp
+        .eval(&tcl_script!("list", hostile, "").to_string())
+        .map_err(|obj| obj.get_string().to_string())?;
+    assert_eq!(
+        result.get_string(),
+        format!("{} {{}}", quote_element(hostile))
+    );
+
+    let callback = tcl_script!("string", "length", hostile).into_object();
+    assert_eq!(callback.get_type_name(), "list");
+    let result = interp.eval_obj(&callback, EvalFlags::NONE)?;
+    assert_eq!(result.get_string(), hostile.len().to_string());
+
+    Ok(())
+}
@@ -0,0 +1,331 @@
+use std::cell::Cell;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use rtea::*;
+
+mod common;
+
+use common::TestInterpreter;
+
+struct Pool {
+    connections: Cell<usize>,
+    dropped: Rc<Cell<bool>>,
+}
+
+impl Drop for Pool {
+    fn drop(&mut self) {
+        self.dropped.set(true);
+    }
+}
+
+#[test]
+fn state() -> Result<(), String> {
+    let test_interp = TestInterpreter::new();
+    test_interp.as_ref().init_global_functions().unwrap();
+    let interp = test_interp.as_ref();
+
+    fn connect(interp: &Interpreter, _args: Vec<&str>) -> Result<TclStatus, String> {
+        let pool = interp.state::<Pool>().ok_or("no pool")?;
+        pool.connections.set(pool.connections.get() + 1);
+        interp.set_result(&pool.connections.get().to_string());
+        Ok(TclStatus::Ok)
+    }
+
+    let dropped = Rc::new(Cell::new(false));
+    assert!(interp.state::<Pool>().is_none());
+    assert!(
+        interp
+            .set_state(Pool {
+                connections: Cell::new(0),
+                dropped: dropped.clone(),
+            })
+            .is_ok()
+    );
+    assert!(interp.set_state(5u32).is_ok());
+    assert!(interp.set_state(6u32).is_err());
+    assert_eq!(interp.state::<u32>(), Some(&5));
+
+    interp.create_command("connect", connect)?;
+    let result = interp
+        .eval("connect; connect")
+        .map_err(|obj| obj.get_string().to_string())?;
+    assert_eq!(result.get_string(), "2");
+
+    assert!(unsafe { interp.remove_state::<u32>() });
+    assert!(!unsafe { interp.remove_state::<u32>() });
+    assert!(interp.state::<u32>().is_none());
+
+    assert!(!dropped.get());
+    drop(test_interp);
+    assert!(dropped.get());
+
+    Ok(())
+}
+
+#[test]
+fn delete_hooks() -> Result<(), String> {
+    let test_interp = TestInterpreter::new();
+    test_interp.as_ref().init_global_functions().unwrap();
+    let interp = test_interp.as_ref();
+
+    let events = Rc::new(RefCell::new(Vec::new()));
+
+    fn handle(_interp: &Interpreter, _args: Vec<&str>) -> Result<TclStatus, String> {
+        Ok(TclStatus::Ok)
+    }
+    interp.create_command("handle", handle)?;
+    let log = events.clone();
+    interp
+        .trace_command("handle", CommandTraceFlags::DELETE, move |_, _, _, _| {
+            log.borrow_mut().push("command");
+        })?
+        .detach();
+
+    struct Log(Rc<RefCell<Vec<&'static str>>>);
+    impl Drop for Log {
+        fn drop(&mut self) {
+            self.0.borrow_mut().push("state");
+        }
+    }
+    let _ = interp.set_state(Log(events.clone()));
+
+    let log = events.clone();
+    let flush = interp.call_when_deleted(move |interp| {
+        assert!(interp.state::<Log>().is_some());
+        log.borrow_mut().push("flush")
+    });
+    let log = events.clone();
+    let removed = interp.call_when_deleted(move |_| log.borrow_mut().push("removed"));
+    drop(removed);
+    let log = events.clone();
+    interp
+        .call_when_deleted(move |_| log.borrow_mut().push("close"))
+        .detach();
+
+    let exit = interp.create_exit_handler(|| {});
+    assert!(exit.is_active());
+    drop(exit);
+    let thread_exit = interp.create_thread_exit_handler(|| {});
+    assert!(thread_exit.is_active());
+    drop(thread_exit);
+
+    assert!(flush.is_active());
+    drop(test_interp);
+    assert!(!flush.is_active());
+    drop(flush);
+    assert_eq!(*events.borrow(), vec!["command", "flush", "close", "state"]);
+
+    Ok(())
+}
+
+thread_local! {
+    static UNLOADED: RefCell<Vec<&'static str>> = const { RefCell::new(Vec::new()) };
+}
+
+struct Session;
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        UNLOADED.with_borrow_mut(|unloaded| unloaded.push("state"));
+    }
+}
+
+#[module_init(Unloadable, "1.0")]
+fn unloadable_init(interp: &Interpreter) -> Result<TclStatus, String> {
+    fn cmd(_interp: &Interpreter, _args: Vec<&str>) -> Result<TclStatus, String> {
+        Ok(TclStatus::Ok)
+    }
+    fn counter(
+        interp: &Interpreter,
+        count: &Cell<usize>,
+        _args: Vec<&str>,
+    ) -> Result<TclStatus, String> {
+        count.set(count.get() + 1);
+        interp.set_result(&count.get().to_string());
+        Ok(TclStatus::Ok)
+    }
+
+    interp.create_command("unloadable::cmd", cmd)?;
+    interp.create_command("unloadable::renamed", cmd)?;
+    StatefulCommand::new(counter, Cell::new(0)).attach_command(interp, "unloadable::counter")?;
+    let _ = interp.set_state(Session);
+    interp
+        .trace_command(
+            "unloadable::cmd",
+            CommandTraceFlags::DELETE,
+            |_, _, _, _| {
+                UNLOADED.with_borrow_mut(|unloaded| unloaded.push("command"));
+            },
+        )?
+        .detach();
+    std::mem::forget(
+        interp.trace_var("watched", None, VarTraceFlags::WRITES, |_, _, _, _| {
+            Err("traced".to_string())
+        })?,
+    );
+    Ok(TclStatus::Ok)
+}
+
+#[module_unload(Unloadable)]
+fn unloadable_unload(_interp: &Interpreter, _flags: TclUnloadFlag) -> Result<TclStatus, String> {
+    UNLOADED.with_borrow_mut(|unloaded| unloaded.push("unload"));
+    Ok(TclStatus::Ok)
+}
+
+#[test]
+fn unload_cleanup() -> Result<(), String> {
+    let test_interp = TestInterpreter::new();
+    assert_eq!(Unloadable_Init(test_interp.as_ptr()), TclStatus::Ok);
+    let interp = test_interp.as_ref();
+
+    fn other(_interp: &Interpreter, _args: Vec<&str>) -> Result<TclStatus, String> {
+        Ok(TclStatus::Ok)
+    }
+    interp.create_command("unloadable::other", other)?;
+    interp
+        .eval("unloadable::counter; rename unloadable::renamed ::moved")
+        .map_err(|obj| obj.get_string().to_string())?;
+    assert!(interp.eval("set watched 1").is_err());
+
+    assert_eq!(
+        Unloadable_Unload(test_interp.as_ptr(), TclUnloadFlag::DetachFromInterpreter),
+        TclStatus::Ok
+    );
+    let commands = interp
+        .eval("lsort [concat [info commands unloadable::*] [info commands moved]]")
+        .map_err(|obj| obj.get_string().to_string())?;
+    assert_eq!(commands.get_string(), "::unloadable::other");
+    assert!(interp.eval("set watched 2").is_ok());
+    assert!(interp.state::<Session>().is_none());
+    // The detached command trace was removed before its command was deleted.
+    UNLOADED.with_borrow(|unloaded| assert_eq!(*unloaded, vec!["unload", "state"]));
+
+    Ok(())
+}
+
+#[derive(Debug, Clone, TclObjectType)]
+struct Handle(u32);
+
+impl std::fmt::Display for Handle {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "handle{}", self.0)
+    }
+}
+
+#[module_init(Handles, "1.0")]
+fn handles_init(interp: &Interpreter) -> Result<TclStatus, String> {
+    fn open(interp: &Interpreter, _args: Vec<Object>) -> Result<TclStatus, Object> {
+        interp.set_obj_result(&Handle(1).into());
+        Ok(TclStatus::Ok)
+    }
+
+    interp.register_obj_type::<Handle>();
+    interp.create_obj_command("handles::open", open)?;
+    Ok(TclStatus::Ok)
+}
+
+#[module_unload(Handles)]
+fn handles_unload(_interp: &Interpreter, _flags: TclUnloadFlag) -> Result<TclStatus, String> {
+    Ok(TclStatus::Ok)
+}
+
+#[test]
+fn unload_live_objects() -> Result<(), String> {
+    let test_interp = TestInterpreter::new();
+    assert_eq!(Handles_Init(test_interp.as_ptr()), TclStatus::Ok);
+    let interp = test_interp.as_ref();
+
+    interp
+        .eval("set h [handles::open]; return")
+        .map_err(|obj| obj.get_string().to_string())?;
+    assert_eq!(Handle::live_objects(), 1);
+    assert_eq!(
+        Handles_Unload(test_interp.as_ptr(), TclUnloadFlag::DetachFromProcesss),
+        TclStatus::Error
+    );
+    assert_eq!(
+        interp.get_obj_result().get_string(),
+        "cannot unload \"Handles\": 1 objects of type \"Handle\" are still in use"
+    );
+    assert!(
+        interp
+            .eval("info commands handles::open")
+            .unwrap()
+            .get_string()
+            != ""
+    );
+
+    interp
+        .eval("unset h")
+        .map_err(|obj| obj.get_string().to_string())?;
+    assert_eq!(Handle::live_objects(), 0);
+    assert_eq!(
+        Handles_Unload(test_interp.as_ptr(), TclUnloadFlag::DetachFromProcesss),
+        TclStatus::Ok
+    );
+    assert_eq!(
+        interp
+            .eval("info commands handles::*")
+            .unwrap()
+            .get_string(),
+        ""
+    );
+
+    Ok(())
+}
+
+thread_local! {
+    static FIRED: RefCell<Vec<&'static str>> = const { RefCell::new(Vec::new()) };
+}
+
+#[module_init(Hooks, "1.0")]
+fn hooks_init(interp: &Interpreter) -> Result<TclStatus, String> {
+    interp
+        .call_when_deleted(|_| FIRED.with_borrow_mut(|fired| fired.push("deleted")))
+        .detach();
+    interp.create_exit_handler(|| {}).detach();
+    interp
+        .create_thread_exit_handler(|| FIRED.with_borrow_mut(|fired| fired.push("thread exit")))
+        .detach();
+    std::mem::forget(interp.limits().add_handler(LimitType::Commands, |_| {
+        FIRED.with_borrow_mut(|fired| fired.push("limit"))
+    }));
+    std::mem::forget(interp.link_var("hooks::linked", 1i32, LinkMode::ReadOnly)?);
+    Ok(TclStatus::Ok)
+}
+
+#[module_unload(Hooks)]
+fn hooks_unload(_interp: &Interpreter, _flags: TclUnloadFlag) -> Result<TclStatus, String> {
+    Ok(TclStatus::Ok)
+}
+
+#[test]
+fn unload_handlers() -> Result<(), String> {
+    let test_interp = TestInterpreter::new();
+    let interp = test_interp.as_ref();
+    interp
+        .eval("namespace eval hooks {}")
+        .map_err(|obj| obj.get_string().to_string())?;
+    assert_eq!(Hooks_Init(test_interp.as_ptr()), TclStatus::Ok);
+    assert!(interp.eval("set hooks::linked 2").is_err());
+
+    assert_eq!(
+        Hooks_Unload(test_interp.as_ptr(), TclUnloadFlag::DetachFromInterpreter),
+        TclStatus::Ok
+    );
+    interp
+        .eval("set hooks::linked 2")
+        .map_err(|obj| obj.get_string().to_string())?;
+
+    let limits = interp.limits();
+    limits.set_commands(0);
+    limits.enable(LimitType::Commands);
+    assert!(interp.eval("set x 1").is_err());
+    limits.disable(LimitType::Commands);
+
+    drop(test_interp);
+    FIRED.with_borrow(|fired| assert!(fired.is_empty(), "{:?} ran after unload", fired));
+
+    Ok(())
+}
@@ -0,0 +1,121 @@
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::Duration;
+use std::time::SystemTime;
+
+use rtea::*;
+
+mod common;
+
+use common::TestInterpreter;
+
+#[test]
+fn cancel() -> Result<(), String> {
+    let test_interp = TestInterpreter::new();
+    test_interp.as_ref().init_global_functions().unwrap();
+    let interp = test_interp.as_ref();
+
+    fn spin(interp: &Interpreter, _args: Vec<Object>) -> Result<TclStatus, Object> {
+        loop {
+            interp.check_canceled()?;
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+    interp.create_obj_command("spin", spin)?;
+
+    let handle = interp.cancel_handle();
+    let supervisor = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(50));
+        handle.cancel(false, Some("too slow"))
+    });
+
+    let err = interp.eval("spin").expect_err("spin should be canceled");
+    assert_eq!(err.get_string(), "too slow");
+    supervisor.join().unwrap()?;
+
+    let code = interp
+        .eval("set errorCode")
+        .map_err(|obj| obj.get_string().to_string())?;
+    let expected = if cfg!(feature = "tcl86") {
+        "TCL CANCEL ICANCEL {too slow}"
+    } else {
+        "TCL CANCEL IEVAL"
+    };
+    assert_eq!(code.get_string(), expected);
+
+    let handle = interp.cancel_handle();
+    drop(test_interp);
+    assert!(handle.cancel(false, None).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn command_limit() -> Result<(), String> {
+    let test_interp = TestInterpreter::new();
+    test_interp.as_ref().init_global_functions().unwrap();
+    let interp = test_interp.as_ref();
+
+    let count: usize = interp
+        .eval("info cmdcount")
+        .map_err(|obj| obj.get_string().to_string())?
+        .get_string()
+        .parse()
+        .map_err(|_| "bad cmdcount".to_string())?;
+
+    let limits = interp.limits();
+    limits.set_commands(count + 100);
+    limits.enable(LimitType::Commands);
+    assert!(limits.is_enabled(LimitType::Commands));
+
+    let extensions = Rc::new(Cell::new(0));
+    let seen = extensions.clone();
+    let handler = limits.add_handler(LimitType::Commands, move |interp| {
+        if seen.get() == 0 {
+            let limits = interp.limits();
+            limits.set_commands(limits.commands() + 100);
+        }
+        seen.set(seen.get() + 1);
+    });
+
+    let err = interp
+        .eval("proc step {} { incr ::i }; while {1} { step }")
+        .expect_err("loop should hit the command limit");
+    assert_eq!(err.get_string(), "command count limit exceeded");
+    assert_eq!(extensions.get(), 2);
+    assert!(limits.is_exceeded(LimitType::Commands));
+
+    drop(handler);
+    limits.disable(LimitType::Commands);
+    interp
+        .eval("set ok 1")
+        .map_err(|obj| obj.get_string().to_string())?;
+
+    Ok(())
+}
+
+#[test]
+fn time_limit() -> Result<(), String> {
+    let test_interp = TestInterpreter::new();
+    test_interp.as_ref().init_global_functions().unwrap();
+    let interp = test_interp.as_ref();
+
+    fn spin(interp: &Interpreter, _args: Vec<Object>) -> Result<TclStatus, Object> {
+        loop {
+            interp.check_limits()?;
+        }
+    }
+    interp.create_obj_command("spin", spin)?;
+
+    let limits = interp.limits();
+    limits.set_time(SystemTime::now() + Duration::from_millis(50));
+    limits.enable(LimitType::Time);
+
+    let err = interp
+        .eval("spin")
+        .expect_err("spin should hit the time limit");
+    assert_eq!(err.get_string(), "time limit exceeded");
+    assert!(interp.limit_exceeded());
+
+    Ok(())
+}
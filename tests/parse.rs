@@ -0,0 +1,101 @@
+use rtea::*;
+
+mod common;
+
+use common::TestInterpreter;
+
+#[test]
+fn parse_script() -> Result<(), String> {
+    let test_interp = TestInterpreter::new();
+    test_interp.as_ref().init_global_functions().unwrap();
+    let interp = test_interp.as_ref();
+
+    let script = "# setup\nset a {b c}\nputs \"x $a(1) [incr i]\\n\"; {*}$args\n";
+    let commands = interp.parse_script(script).collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(commands.len(), 3);
+
+    let set = &commands[0];
+    assert_eq!(&script[set.comment.clone().unwrap()], "# setup\n");
+    assert_eq!(set.words.len(), 3);
+    assert!(set.words[2].simple);
+    assert_eq!(&script[set.words[2].range.clone()], "{b c}");
+    assert_eq!(&script[set.words[2].tokens[0].range.clone()], "b c");
+
+    let puts = &commands[1];
+    let kinds: Vec<TokenKind> = puts.words[1]
+        .tokens
+        .iter()
+        .map(|token| token.kind)
+        .collect();
+    assert_eq!(
+        kinds,
+        vec![
+            TokenKind::Text,
+            TokenKind::Variable,
+            TokenKind::Text,
+            TokenKind::Command,
+            TokenKind::Backslash,
+        ]
+    );
+    let variable = &puts.words[1].tokens[1];
+    assert_eq!(&script[variable.range.clone()], "$a(1)");
+    assert_eq!(&script[variable.components[0].range.clone()], "a");
+    let substitution = &puts.words[1].tokens[3];
+    assert_eq!(&script[substitution.range.clone()], "[incr i]");
+
+    let nested = &script[substitution.range.start + 1..];
+    let inner = interp
+        .parse_command_substitution(nested)
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(inner.len(), 1);
+    assert_eq!(&nested[inner[0].words[0].range.clone()], "incr");
+
+    let expand = &commands[2].words[0];
+    assert!(expand.expand);
+    assert_eq!(&script[expand.range.clone()], "{*}$args");
+
+    let err = interp
+        .parse_script("set a {b")
+        .find_map(Result::err)
+        .expect("unclosed brace should fail");
+    assert!(err.incomplete);
+    assert_eq!(err.message, "missing close-brace");
+
+    Ok(())
+}
+
+#[test]
+fn parse_fragments() -> Result<(), String> {
+    let test_interp = TestInterpreter::new();
+    test_interp.as_ref().init_global_functions().unwrap();
+    let interp = test_interp.as_ref();
+
+    let expr = interp.parse_expr("$x + 1")?;
+    assert_eq!(expr.kind, TokenKind::SubExpr);
+    assert_eq!(expr.components[0].kind, TokenKind::Operator);
+
+    let (tokens, end) = interp.parse_braces("{a {b}} rest")?;
+    assert_eq!(end, 7);
+    assert_eq!(tokens[0].range, 1..6);
+
+    let (tokens, end) = interp.parse_quoted_string("\"a $b\" rest")?;
+    assert_eq!(end, 6);
+    assert_eq!(tokens[1].kind, TokenKind::Variable);
+
+    let token = interp.parse_var_name("${a b}c")?;
+    assert_eq!(token.kind, TokenKind::Variable);
+    assert_eq!(token.range, 0..6);
+
+    interp.set_result("stale");
+    let err = interp
+        .parse_var_name("")
+        .expect_err("empty text has no variable");
+    assert!(err.incomplete);
+    assert_ne!(err.message, "stale");
+
+    assert!(interp.command_complete("set a {b}")?);
+    assert!(!interp.command_complete("proc p {} {")?);
+    assert!(interp.command_complete("a\0b").is_err());
+
+    Ok(())
+}
@@ -0,0 +1,202 @@
+//! Checks the stubs tables generated by `build.rs` against the headers of a
+//! Tcl installation.
+//!
+//! The headers are looked up in `TCL_INCLUDE_DIR` (if set) and the usual
+//! install locations.  The internal tables are only checked if Tcl's private
+//! headers are installed as well.
+
+use std::path::Path;
+use std::path::PathBuf;
+
+mod generated {
+    #![allow(dead_code, unused_imports)]
+
+    use rtea::Interpreter;
+    use rtea::ObjectType;
+    use rtea::RawObject;
+    use rtea::TclSize;
+
+    include!(concat!(env!("OUT_DIR"), "/stubs.rs"));
+}
+
+#[cfg(feature = "tcl86")]
+const VERSION: &str = "8.6";
+#[cfg(feature = "tcl90")]
+const VERSION: &str = "9.0";
+
+/// Stubs tables the headers declare but `stubs/` has no declarations for,
+/// which the generator leaves opaque behind the `hooks` pointer.
+#[cfg(feature = "tcl86")]
+const UNDECLARED: &[&str] = &[];
+#[cfg(feature = "tcl90")]
+const UNDECLARED: &[&str] = &["TclIntStubs", "TclIntPlatStubs"];
+
+const HEADERS: &[&str] = &[
+    "tclDecls.h",
+    "tclPlatDecls.h",
+    "tclIntDecls.h",
+    "tclIntPlatDecls.h",
+];
+
+fn include_dirs() -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = std::env::var_os("TCL_INCLUDE_DIR")
+        .map(|dirs| std::env::split_paths(&dirs).collect())
+        .unwrap_or_default();
+    for prefix in ["/usr/include", "/usr/local/include"] {
+        let versioned = Path::new(prefix).join(format!("tcl{}", VERSION));
+        dirs.push(versioned.join("tcl-private").join("generic"));
+        dirs.push(versioned);
+        dirs.push(PathBuf::from(prefix));
+    }
+    dirs
+}
+
+/// Checks whether the `tcl.h` next to a header is for the Tcl version the
+/// tables were generated for.
+fn matches_version(header: &Path) -> bool {
+    let Ok(tcl_h) = std::fs::read_to_string(header.with_file_name("tcl.h")) else {
+        return false;
+    };
+    let define = |name: &str| {
+        tcl_h
+            .lines()
+            .find_map(|line| line.strip_prefix(&format!("#define {}", name)))
+            .map(|value| value.trim().to_string())
+    };
+    let version = format!(
+        "{}.{}",
+        define("TCL_MAJOR_VERSION").unwrap_or_default(),
+        define("TCL_MINOR_VERSION").unwrap_or_default()
+    );
+    version == VERSION
+}
+
+/// Reads the names of the entries of a stubs table from its header, taking
+/// the branch for the current platform where they differ.
+fn header_entries(header: &str, table: &str) -> Option<Vec<String>> {
+    let start = header.find(&format!("typedef struct {} {{", table))?;
+    let end = start + header[start..].find(&format!("}} {};", table))?;
+
+    let mut entries = vec!["magic".to_string(), "hooks".to_string()];
+    let mut included: Vec<bool> = Vec::new();
+    for line in header[start..end].lines().skip(3) {
+        let line = line.trim();
+        if line.starts_with("#if") {
+            let platform = if line.ends_with("/* UNIX */") {
+                cfg!(all(unix, not(target_os = "macos")))
+            } else if line.ends_with("/* WIN */") {
+                cfg!(windows)
+            } else if line.ends_with("/* MACOSX */") {
+                cfg!(target_os = "macos")
+            } else {
+                panic!("unexpected condition in {}: {}", table, line);
+            };
+            included.push(platform);
+        } else if line.starts_with("#else") {
+            let platform = included.pop().expect("#else without #if");
+            included.push(!platform);
+        } else if line.starts_with("#endif") {
+            included.pop().expect("#endif without #if");
+        } else if let Some(name) = line.split("(*").nth(1)
+            && included.iter().all(|included| *included)
+        {
+            entries.push(name[..name.find(')').unwrap()].to_string());
+        }
+    }
+
+    Some(entries)
+}
+
+/// Finds the names of the stubs tables a header declares.
+fn header_tables(header: &str) -> Vec<&str> {
+    header
+        .lines()
+        .filter_map(|line| line.strip_prefix("typedef struct "))
+        .filter_map(|rest| rest.strip_suffix(" {"))
+        .filter(|name| name.ends_with("Stubs"))
+        .collect()
+}
+
+/// Gets the name of the stubs table entry for a function (`Tcl_Eval` is
+/// stored as `tcl_Eval`).
+fn entry_name(function: &str) -> String {
+    let mut chars = function.chars();
+    match chars.next() {
+        Some(first) => first.to_lowercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[test]
+fn layout_matches_headers() {
+    let dirs = include_dirs();
+    let mut checked = Vec::new();
+
+    for file in HEADERS {
+        let Some(path) = dirs
+            .iter()
+            .map(|dir| dir.join(file))
+            .find(|path| path.exists() && matches_version(path))
+        else {
+            continue;
+        };
+        let header = std::fs::read_to_string(&path).unwrap();
+
+        for table in header_tables(&header) {
+            let generated = generated::STUB_LAYOUTS
+                .iter()
+                .any(|(name, _, _)| *name == table);
+            assert!(
+                generated || UNDECLARED.contains(&table),
+                "{} declares {}, but no declarations were generated for it",
+                path.display(),
+                table
+            );
+            assert!(
+                !generated || !UNDECLARED.contains(&table),
+                "{} is generated now, so it should not be listed as undeclared",
+                table
+            );
+            if !generated {
+                eprintln!("not checking {}: no declarations in stubs/", table);
+            }
+        }
+
+        for (table, size, layout) in generated::STUB_LAYOUTS {
+            let Some(entries) = header_entries(&header, table) else {
+                continue;
+            };
+            let pointer = size_of::<*const ()>();
+            assert_eq!(
+                *size,
+                entries.len() * pointer,
+                "{} has {} entries in {}",
+                table,
+                entries.len(),
+                path.display()
+            );
+            for (function, offset) in *layout {
+                let name = entry_name(function);
+                assert_eq!(
+                    entries.get(offset / pointer),
+                    Some(&name),
+                    "{} is at offset {} of {}, but {} has a different entry there",
+                    function,
+                    offset,
+                    table,
+                    path.display()
+                );
+            }
+            checked.push(*table);
+        }
+    }
+
+    if checked.is_empty() {
+        eprintln!(
+            "skipping: no Tcl {} headers found (set TCL_INCLUDE_DIR)",
+            VERSION
+        );
+    } else {
+        eprintln!("checked {}", checked.join(", "));
+    }
+}
@@ -0,0 +1,17 @@
+//! Runs the tcltest suites in `tests/tcl` against the Harness extension.
+
+use rtea::*;
+
+#[module_init(Harness, "1.0")]
+fn harness_init(interp: &Interpreter) -> Result<TclStatus, String> {
+    fn shout(interp: &Interpreter, args: Vec<&str>) -> Result<TclStatus, String> {
+        let word = args.get(1).ok_or("usage: shout word")?;
+        interp.set_result(&word.to_uppercase());
+        Ok(TclStatus::Ok)
+    }
+
+    interp.create_command("shout", shout)?;
+    Ok(TclStatus::Ok)
+}
+
+rtea::tcltest_main!("tests/tcl", Harness_Init);
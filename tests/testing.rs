@@ -0,0 +1,220 @@
+#![cfg(feature = "testing")]
+
+use rtea::testing::TclTestOutcome;
+use rtea::testing::TclTestSuite;
+use rtea::testing::TestInterpreter;
+use rtea::*;
+
+#[module_init(Harness, "1.0")]
+fn harness_init(interp: &Interpreter) -> Result<TclStatus, String> {
+    fn shout(interp: &Interpreter, args: Vec<&str>) -> Result<TclStatus, String> {
+        let word = args.get(1).ok_or("usage: shout word")?;
+        interp.set_result(&word.to_uppercase());
+        Ok(TclStatus::Ok)
+    }
+
+    interp.create_command("shout", shout)?;
+    Ok(TclStatus::Ok)
+}
+
+#[test]
+fn assertions() {
+    let interp = TestInterpreter::new();
+    interp.init(Harness_Init).unwrap();
+
+    assert_tcl_eq!(interp, "shout hello", "HELLO");
+    assert_tcl_eq!(&*interp, String::from("string length abc"), "3");
+    assert_tcl_error!(interp, "shout");
+    assert_tcl_error!(interp, "expr {1 / 0}", "ARITH DIVZERO");
+    assert_tcl_error!(interp, "expr {1 / 0}", "ARITH DIVZERO {divide by zero}");
+    assert_eq!(
+        interp.eval("package present harness").unwrap().get_string(),
+        "1.0"
+    );
+}
+
+#[test]
+#[should_panic(expected = "unexpected result of script `shout a`")]
+fn assert_eq_mismatch() {
+    let interp = TestInterpreter::new();
+    interp.init(Harness_Init).unwrap();
+    assert_tcl_eq!(interp, "shout a", "a");
+}
+
+#[test]
+#[should_panic(expected = "script `shout` failed: usage: shout word")]
+fn assert_eq_failure() {
+    let interp = TestInterpreter::new();
+    interp.init(Harness_Init).unwrap();
+    assert_tcl_eq!(interp, "shout", "");
+}
+
+#[test]
+#[should_panic(
+    expected = "failed with error code `ARITH DIVZERO {divide by zero}` instead of `ARITH IOVERFLOW`"
+)]
+fn assert_error_code_mismatch() {
+    let interp = TestInterpreter::new();
+    assert_tcl_error!(interp, "expr {1 / 0}", "ARITH IOVERFLOW");
+}
+
+#[test]
+#[should_panic(expected = "succeeded with `ok` instead of failing")]
+fn assert_error_success() {
+    let interp = TestInterpreter::new();
+    assert_tcl_error!(interp, "set x ok");
+}
+
+#[test]
+fn output_capture() {
+    let interp = TestInterpreter::new();
+    interp
+        .eval("puts hello; puts -nonewline stderr \"caf\u{e9}\"; puts stdout world")
+        .unwrap();
+    assert_eq!(interp.stdout(), "hello\nworld\n");
+    assert_eq!(interp.take_stdout(), "hello\nworld\n");
+    assert_eq!(interp.stdout(), "");
+    assert_eq!(interp.take_stderr(), "caf\u{e9}");
+    interp.eval("puts -nonewline \"a\\0b\"").unwrap();
+    assert_eq!(interp.take_stdout(), "a\0b");
+
+    // A second interpreter on the thread captures its own output.
+    let other = TestInterpreter::new();
+    other.eval("puts other").unwrap();
+    assert_eq!(other.stdout(), "other\n");
+    drop(other);
+
+    interp.eval("puts again").unwrap();
+    assert_eq!(interp.stdout(), "again\n");
+
+    // Captures may end in any order.
+    let other = TestInterpreter::new();
+    let last = TestInterpreter::new();
+    drop(other);
+    last.eval("puts last").unwrap();
+    assert_eq!(last.stdout(), "last\n");
+    drop(last);
+    interp.eval("puts restored").unwrap();
+    assert_eq!(interp.stdout(), "again\nrestored\n");
+}
+
+#[test]
+fn isolation() {
+    let first = TestInterpreter::new();
+    first.eval("set x 1; proc p {} {}").unwrap();
+
+    let second = TestInterpreter::new();
+    assert_tcl_error!(second, "set x");
+    assert_tcl_eq!(second, "info procs p", "");
+}
+
+#[test]
+fn tcltest_failures() {
+    let dir = std::env::temp_dir().join(format!("rtea-tcltest-failures-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("a.test"),
+        r#"
+package require tcltest 2
+package require harness
+tcltest::test ok-1 {} {shout a} A
+tcltest::test bad-1 {wrong result} {shout b} b
+tcltest::test bad-2 {wrong result} {shout c} c
+exit 1
+"#,
+    )
+    .unwrap();
+    std::fs::write(dir.join("b.test"), "error {broken file}").unwrap();
+    std::fs::write(dir.join("notes.txt"), "error {not a test}").unwrap();
+
+    let report = TclTestSuite::new(&dir).init(Harness_Init).run();
+    let outcomes: Vec<_> = report
+        .cases
+        .iter()
+        .map(|case| (case.id(), case.outcome))
+        .collect();
+    assert_eq!(
+        outcomes,
+        [
+            ("a.test::ok-1".to_string(), TclTestOutcome::Passed),
+            ("a.test::bad-1".to_string(), TclTestOutcome::Failed),
+            ("a.test::bad-2".to_string(), TclTestOutcome::Failed),
+            // `exit` is hidden, so it fails the file rather than the process.
+            ("a.test".to_string(), TclTestOutcome::Failed),
+            ("b.test".to_string(), TclTestOutcome::Failed),
+        ]
+    );
+    assert!(
+        report.cases[1]
+            .output
+            .starts_with("==== bad-1 wrong result FAILED")
+    );
+    assert!(report.cases[1].output.ends_with("==== bad-1 FAILED"));
+    assert!(!report.cases[1].output.contains("bad-2"));
+    assert!(report.cases[4].output.contains("broken file"));
+    assert!(!report.success());
+    assert!(
+        report
+            .to_string()
+            .contains("test result: FAILED. 1 passed; 4 failed")
+    );
+
+    let filtered = TclTestSuite::new(&dir)
+        .init(Harness_Init)
+        .filter("bad-2")
+        .run();
+    assert_eq!(filtered.count(TclTestOutcome::Failed), 3);
+    assert_eq!(filtered.count(TclTestOutcome::Filtered), 2);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn tcltest_list() {
+    let dir = std::env::temp_dir().join(format!("rtea-tcltest-list-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("a.test"),
+        r#"
+package require tcltest 2
+tcltest::test first-1 {} {set ::ran 1} 2
+tcltest::test second-1 {} {set ::ran 1} 2
+"#,
+    )
+    .unwrap();
+
+    let ids: Vec<_> = TclTestSuite::new(&dir)
+        .list()
+        .iter()
+        .map(|case| (case.id(), case.outcome))
+        .collect();
+    assert_eq!(
+        ids,
+        [
+            ("a.test::first-1".to_string(), TclTestOutcome::Listed),
+            ("a.test::second-1".to_string(), TclTestOutcome::Listed),
+        ]
+    );
+
+    let filtered = TclTestSuite::new(&dir).filter("second").list();
+    assert_eq!(filtered.len(), 1);
+    assert_eq!(filtered[0].id(), "a.test::second-1");
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+#[should_panic(expected = "a.test::bad-1")]
+fn tcltest_assert_success() {
+    let dir = std::env::temp_dir().join(format!("rtea-tcltest-assert-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("a.test"),
+        "package require tcltest 2\ntcltest::test bad-1 {} {set x 1} 2\n",
+    )
+    .unwrap();
+
+    let report = TclTestSuite::new(&dir).run();
+    std::fs::remove_dir_all(&dir).unwrap();
+    report.assert_success();
+}
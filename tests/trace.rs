@@ -0,0 +1,137 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use rtea::*;
+
+mod common;
+
+use common::TestInterpreter;
+
+#[test]
+fn trace_exec() -> Result<(), String> {
+    let test_interp = TestInterpreter::new();
+    test_interp.as_ref().init_global_functions().unwrap();
+    let interp = test_interp.as_ref();
+
+    let calls = Rc::new(RefCell::new(Vec::new()));
+    let log = calls.clone();
+    let trace = interp.trace_exec(None, ExecTraceFlags::NONE, move |interp, event| {
+        let name = interp.command_full_name(event.token);
+        if name == "::exit" {
+            return Err("exit is disabled".to_string());
+        }
+        log.borrow_mut().push((event.level, name, event.args.len()));
+        Ok(())
+    });
+
+    interp
+        .eval("proc p {a} { set b $a }; p 1")
+        .map_err(|obj| obj.get_string().to_string())?;
+    assert_eq!(
+        calls
+            .borrow()
+            .iter()
+            .map(|(_, name, _)| name.as_str())
+            .collect::<Vec<_>>(),
+        vec!["::proc", "::p", "::set"]
+    );
+    assert_eq!(calls.borrow()[2], (2, "::set".to_string(), 3));
+
+    let err = interp.eval("exit 1").expect_err("trace should veto exit");
+    assert_eq!(err.get_string(), "exit is disabled");
+
+    drop(trace);
+    let count = calls.borrow().len();
+    interp
+        .eval("p 2")
+        .map_err(|obj| obj.get_string().to_string())?;
+    assert_eq!(count, calls.borrow().len());
+
+    let trace = interp.trace_exec(Some(1), ExecTraceFlags::ALLOW_INLINE_COMPILATION, |_, _| {
+        Ok(())
+    });
+    drop(test_interp);
+    assert!(!trace.is_active());
+
+    Ok(())
+}
+
+#[test]
+fn trace_command() -> Result<(), String> {
+    let test_interp = TestInterpreter::new();
+    test_interp.as_ref().init_global_functions().unwrap();
+    let interp = test_interp.as_ref();
+
+    fn handle(_interp: &Interpreter, _args: Vec<&str>) -> Result<TclStatus, String> {
+        Ok(TclStatus::Ok)
+    }
+
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let log = events.clone();
+    let token = interp.create_command("handle1", handle)?;
+    let trace = interp.trace_command(
+        &interp.command_full_name(token),
+        CommandTraceFlags::RENAME | CommandTraceFlags::DELETE,
+        move |_, old, new, flags| {
+            log.borrow_mut().push((
+                old.to_string(),
+                new.map(str::to_string),
+                flags.contains(CommandTraceFlags::DELETE),
+            ));
+        },
+    )?;
+
+    interp
+        .eval("rename handle1 handle2")
+        .map_err(|obj| obj.get_string().to_string())?;
+    assert_eq!(trace.command_name(), "::handle2");
+    interp
+        .eval("rename handle2 {}")
+        .map_err(|obj| obj.get_string().to_string())?;
+    assert!(!trace.is_active());
+    assert_eq!(
+        *events.borrow(),
+        vec![
+            (
+                "::handle1".to_string(),
+                Some("::handle2".to_string()),
+                false
+            ),
+            ("::handle2".to_string(), None, true),
+        ]
+    );
+    drop(trace);
+
+    let count = Rc::new(RefCell::new(0));
+    let deleted = count.clone();
+    let token = StatefulCommand::new(|_, _: &(), _| Ok(TclStatus::Ok), ())
+        .attach_command(interp, "handle3")?;
+    assert_eq!(interp.command_full_name(token), "::handle3");
+    interp
+        .trace_command(
+            &interp.command_full_name(token),
+            CommandTraceFlags::DELETE,
+            move |_, _, _, _| {
+                *deleted.borrow_mut() += 1;
+            },
+        )?
+        .detach();
+    interp
+        .trace_command("handle3", CommandTraceFlags::RENAME, |_, _, _, _| {
+            panic!("dropped trace should not run");
+        })
+        .map(drop)?;
+    interp
+        .eval("rename handle3 handle4")
+        .map_err(|obj| obj.get_string().to_string())?;
+    assert_eq!(*count.borrow(), 0);
+    assert!(
+        interp
+            .trace_command("missing", CommandTraceFlags::DELETE, |_, _, _, _| {})
+            .is_err()
+    );
+    drop(test_interp);
+    assert_eq!(*count.borrow(), 1);
+
+    Ok(())
+}
@@ -0,0 +1,17 @@
+//! Using rtea before any interpreter is initialized.
+//!
+//! This needs its own test binary, as the global functions stay initialized
+//! for the rest of the process once any test initializes an interpreter.
+
+use rtea::*;
+
+#[test]
+fn objects_before_init() {
+    assert!(!rtea::is_initialized());
+    assert!(matches!(Object::try_new(), Err(Error::NotInitialized)));
+    assert!(matches!(
+        Object::try_new_string("early"),
+        Err(Error::NotInitialized)
+    ));
+    assert!(std::panic::catch_unwind(|| Object::new_string("early")).is_err());
+}
@@ -0,0 +1,246 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use rtea::*;
+
+mod common;
+
+use common::TestInterpreter;
+
+#[test]
+fn trace_var() -> Result<(), String> {
+    let test_interp = TestInterpreter::new();
+    test_interp.as_ref().init_global_functions().unwrap();
+    let interp = test_interp.as_ref();
+
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let log = seen.clone();
+    let trace = interp.trace_var(
+        "config",
+        None,
+        VarTraceFlags::WRITES | VarTraceFlags::READS,
+        move |interp, name, _element, flags| {
+            log.borrow_mut().push(name.to_string());
+            if flags.contains(VarTraceFlags::WRITES) {
+                let value = interp
+                    .eval("set config")
+                    .map_err(|obj| obj.get_string().to_string())?;
+                if value.get_string() == "bad" {
+                    return Err("rejected".to_string());
+                }
+            }
+            Ok(())
+        },
+    )?;
+
+    interp
+        .eval("set config good")
+        .map_err(|obj| obj.get_string().to_string())?;
+    let err = interp
+        .eval("set config bad")
+        .expect_err("trace should veto the write");
+    assert_eq!(err.get_string(), "can't set \"config\": rejected");
+    assert!(!seen.borrow().is_empty());
+
+    drop(trace);
+    let count = seen.borrow().len();
+    interp
+        .eval("set config again")
+        .map_err(|obj| obj.get_string().to_string())?;
+    assert_eq!(count, seen.borrow().len());
+
+    Ok(())
+}
+
+#[test]
+fn trace_var_outlives_interp() -> Result<(), String> {
+    let test_interp = TestInterpreter::new();
+    test_interp.as_ref().init_global_functions().unwrap();
+    let interp = test_interp.as_ref();
+
+    interp
+        .eval("set config 1")
+        .map_err(|obj| obj.get_string().to_string())?;
+    let trace = interp.trace_var("config", None, VarTraceFlags::WRITES, |_, _, _, _| Ok(()))?;
+    assert!(trace.is_active());
+
+    drop(test_interp);
+    assert!(!trace.is_active());
+    drop(trace);
+
+    Ok(())
+}
+
+#[test]
+fn link_var() -> Result<(), String> {
+    let test_interp = TestInterpreter::new();
+    test_interp.as_ref().init_global_functions().unwrap();
+    let interp = test_interp.as_ref();
+
+    let debug = interp.link_var("debug", false, LinkMode::ReadWrite)?;
+    interp
+        .eval("set debug 1")
+        .map_err(|obj| obj.get_string().to_string())?;
+    assert!(debug.get());
+
+    let counter = interp.link_var("counter", 41i64, LinkMode::ReadOnly)?;
+    counter.set(counter.get() + 1);
+    let result = interp
+        .eval("set counter")
+        .map_err(|obj| obj.get_string().to_string())?;
+    assert_eq!(result.get_string(), "42");
+    interp
+        .eval("set counter 0")
+        .expect_err("read-only link should reject writes");
+
+    let name = interp.link_var("name", "rtea".to_string(), LinkMode::ReadWrite)?;
+    interp
+        .eval("append name -tcl")
+        .map_err(|obj| obj.get_string().to_string())?;
+    assert_eq!(name.get(), "rtea-tcl");
+
+    drop(debug);
+    interp
+        .eval("set debug nonsense")
+        .map_err(|obj| obj.get_string().to_string())?;
+
+    drop(test_interp);
+    drop(counter);
+    drop(name);
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "tcl90")]
+fn link_array() -> Result<(), String> {
+    let test_interp = TestInterpreter::new();
+    test_interp.as_ref().init_global_functions().unwrap();
+    let interp = test_interp.as_ref();
+
+    let samples = interp.link_array("samples", vec![1.5f64, 2.5, 3.5], LinkMode::ReadWrite)?;
+    let result = interp
+        .eval("lindex $samples 1")
+        .map_err(|obj| obj.get_string().to_string())?;
+    assert_eq!(result.get_string(), "2.5");
+
+    interp
+        .eval("set samples {0.0 1.0 2.0}")
+        .map_err(|obj| obj.get_string().to_string())?;
+    assert_eq!(samples.to_vec(), vec![0.0, 1.0, 2.0]);
+
+    assert!(samples.set(2, 9.0));
+    assert!(!samples.set(3, 9.0));
+    let result = interp
+        .eval("lindex $samples 2")
+        .map_err(|obj| obj.get_string().to_string())?;
+    assert_eq!(result.get_string(), "9.0");
+
+    Ok(())
+}
+
+#[test]
+fn var_ref() -> Result<(), String> {
+    let test_interp = TestInterpreter::new();
+    test_interp.as_ref().init_global_functions().unwrap();
+    let interp = test_interp.as_ref();
+
+    fn parse(interp: &Interpreter, args: Vec<Object>) -> Result<TclStatus, Object> {
+        let result = interp.var_ref(&args[2]);
+        let previous = if result.exists() {
+            result.get()?.get_string().to_string()
+        } else {
+            String::new()
+        };
+        result.set(&Object::new_string(&format!("{}{}", previous, args[1])))?;
+        Ok(TclStatus::Ok)
+    }
+
+    interp.create_obj_command("parse", parse)?;
+    let result = interp
+        .eval("proc p {} { parse a out; parse b out; return $out }; p")
+        .map_err(|obj| obj.get_string().to_string())?;
+    assert_eq!(result.get_string(), "ab");
+    assert!(interp.get_var(&Object::new_string("out")).is_err());
+
+    interp
+        .eval("parse x arr(key)")
+        .map_err(|obj| obj.get_string().to_string())?;
+    let value = interp
+        .get_var(&Object::new_string("arr(key)"))
+        .map_err(|obj| obj.get_string().to_string())?;
+    assert_eq!(value.get_string(), "x");
+
+    interp
+        .unset_var(&Object::new_string("arr"))
+        .map_err(|obj| obj.get_string().to_string())?;
+    assert!(interp.unset_var(&Object::new_string("arr")).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn arrays() -> Result<(), String> {
+    let test_interp = TestInterpreter::new();
+    test_interp.as_ref().init_global_functions().unwrap();
+    let interp = test_interp.as_ref();
+
+    let table = Object::new_string("table");
+    interp
+        .array_set(
+            &table,
+            vec![
+                ("a", Object::new_string("1")),
+                ("b c", Object::new_string("2")),
+            ],
+        )
+        .map_err(|obj| obj.get_string().to_string())?;
+
+    let mut names = interp
+        .array_names(&table)
+        .map_err(|obj| obj.get_string().to_string())?;
+    names.sort();
+    assert_eq!(names, vec!["a", "b c"]);
+
+    let result = interp
+        .eval("set {table(b c)}")
+        .map_err(|obj| obj.get_string().to_string())?;
+    assert_eq!(result.get_string(), "2");
+
+    interp
+        .eval("set table(d) [list x y]")
+        .map_err(|obj| obj.get_string().to_string())?;
+    let contents = interp
+        .array_get(&table)
+        .map_err(|obj| obj.get_string().to_string())?;
+    assert_eq!(contents.len(), 3);
+    assert_eq!(contents["d"].get_type_name(), "list");
+    assert_eq!(interp.array_iter(&table).map(|it| it.count()).ok(), Some(3));
+
+    interp
+        .eval("set scalar 1")
+        .map_err(|obj| obj.get_string().to_string())?;
+    assert!(interp.array_names(&Object::new_string("scalar")).is_err());
+
+    // Neither a namespace's own `array` nor the interpreter result interfere.
+    fn count(interp: &Interpreter, args: Vec<Object>) -> Result<TclStatus, Object> {
+        interp.set_result("kept");
+        let names = interp.array_names(&args[1])?;
+        assert_eq!(interp.get_obj_result().get_string(), "kept");
+        interp.set_result(&names.len().to_string());
+        Ok(TclStatus::Ok)
+    }
+    interp.create_obj_command("count", count)?;
+    let result = interp
+        .eval(
+            "namespace eval hijack {
+                proc array args { return bogus }
+                proc local {} { set t(x) 1; set t(y) 2; count t }
+                local
+            }",
+        )
+        .map_err(|obj| obj.get_string().to_string())?;
+    assert_eq!(result.get_string(), "2");
+
+    Ok(())
+}